@@ -0,0 +1,235 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::{CompressedImageFormats, ImageSampler, ImageType};
+use bevy::prelude::*;
+use crate::audio::SOUND_FILES;
+use crate::character::{CharacterRegistry, CHARACTER_DIR};
+use crate::components::PipeType;
+use crate::difficulty::{DifficultyCurve, DifficultyPreset};
+use crate::locale::{Language, Locale};
+use crate::parallax::builtin_layers;
+use crate::resources::{number_texture_path, GROUND_TEXTURE, SPACE_TO_START_TEXTURE, WINDOW_ICON};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 界面字体链（相对于资源目录），每种语言使用链中第一个能显示该语言全部文字的字体
+// 目前只附带英文字体，中文界面使用系统字体
+pub const FONT_CHAIN: [&str; 1] = ["fonts/Kenney Future Narrow.ttf"];
+
+// 依次尝试的系统中文字体，只加载第一个可用的
+const SYSTEM_CJK_FONTS: [&str; 9] = [
+    "C:/Windows/Fonts/msyh.ttc",
+    "C:/Windows/Fonts/simhei.ttf",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/STHeiti Medium.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+];
+
+// 资源目录，与 AssetServer 使用的目录一致
+pub fn asset_dir() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets")
+}
+
+// 缺少或无法解析的资源
+#[derive(Debug, Clone)]
+pub struct AssetProblem {
+    pub path: String,
+    pub error: String,
+}
+
+impl fmt::Display for AssetProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.error)
+    }
+}
+
+// 资源检查的结果
+#[derive(Debug, Default)]
+pub struct AssetReport {
+    pub checked: usize, // 检查过的文件数
+    pub problems: Vec<AssetProblem>,
+}
+
+impl AssetReport {
+    fn check(&mut self, path: &str, result: Result<(), String>) {
+        self.checked += 1;
+        if let Err(error) = result {
+            self.problems.push(AssetProblem { path: path.to_string(), error });
+        }
+    }
+}
+
+// 检查角色、管道、数字、地面、背景层、音效等所有引用的贴图和音效，以及难度描述是否存在并能解析
+// 字体由 FontChain::load 单独检查
+pub fn check_assets(asset_dir: &Path) -> AssetReport {
+    let mut report = AssetReport::default();
+
+    let mut images: Vec<String> = PipeType::all_types()
+        .iter()
+        .map(|pipe_type| pipe_type.get_texture_path().to_string())
+        .collect();
+    images.extend((0..10).map(number_texture_path));
+    images.extend([GROUND_TEXTURE, SPACE_TO_START_TEXTURE, WINDOW_ICON].map(String::from));
+    images.extend(builtin_layers().into_iter().flat_map(|layer| layer.textures));
+
+    match CharacterRegistry::load_from_dir(asset_dir) {
+        Ok(characters) => {
+            for character in characters.iter() {
+                images.push(character.texture.clone());
+                if let Some(sheet) = &character.sheet {
+                    images.push(sheet.texture.clone());
+                }
+            }
+        }
+        Err(e) => report.check(CHARACTER_DIR, Err(e.to_string())),
+    }
+
+    images.sort();
+    images.dedup();
+    for path in &images {
+        report.check(path, check_image(&asset_dir.join(path)));
+    }
+    for path in SOUND_FILES {
+        report.check(path, check_sound(&asset_dir.join(path)));
+    }
+    for preset in DifficultyPreset::ALL {
+        let path = preset.path();
+        report.check(&path, check_difficulty(&asset_dir.join(&path)));
+    }
+    report
+}
+
+fn check_image(path: &Path) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| format!("无法读取: {}", e))?;
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    Image::from_buffer(
+        &bytes,
+        ImageType::Extension(extension),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::default(),
+    )
+    .map(|_| ())
+    .map_err(|e| format!("无法解析贴图: {}", e))
+}
+
+fn check_difficulty(path: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("无法读取: {}", e))?;
+    serde_json::from_str::<DifficultyCurve>(&content)
+        .map(|_| ())
+        .map_err(|e| format!("无法解析难度描述: {}", e))
+}
+
+// 音效只检查 Ogg 文件头，解码由音频插件在播放时完成
+fn check_sound(path: &Path) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| format!("无法读取: {}", e))?;
+    if !bytes.starts_with(b"OggS") {
+        return Err("不是 Ogg 音频文件".to_string());
+    }
+    Ok(())
+}
+
+// 每种语言界面上可能显示的文字：字符串表和角色名称
+pub fn language_texts(asset_dir: &Path) -> HashMap<Language, String> {
+    let characters = CharacterRegistry::load_from_dir(asset_dir).ok();
+    Language::ALL
+        .into_iter()
+        .map(|language| {
+            let locale = Locale::new(language);
+            let mut text: String = locale.texts().collect();
+            for character in characters.iter().flat_map(|characters| characters.iter()) {
+                text.push_str(character.display_name(language.code()));
+            }
+            (language, text)
+        })
+        .collect()
+}
+
+// 一个可用的字体文件
+pub struct FontFile {
+    pub path: PathBuf,
+    pub font: Font,
+    pub missing: HashMap<Language, Vec<char>>, // 每种语言缺少字形的文字，为空时能显示该语言的界面
+}
+
+impl FontFile {
+    fn load(path: &Path, texts: &HashMap<Language, String>) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("无法读取: {}", e))?;
+        let face = ttf_parser::Face::parse(&bytes, 0).map_err(|e| format!("无法解析字体: {}", e))?;
+        let missing = texts
+            .iter()
+            .map(|(language, text)| {
+                let mut chars: Vec<char> = text
+                    .chars()
+                    .filter(|c| !c.is_whitespace() && face.glyph_index(*c).is_none())
+                    .collect();
+                chars.sort_unstable();
+                chars.dedup();
+                (*language, chars)
+            })
+            .collect();
+        Ok(Self {
+            path: path.to_path_buf(),
+            font: Font::try_from_bytes(bytes).map_err(|e| format!("无法解析字体: {}", e))?,
+            missing,
+        })
+    }
+
+    pub fn covers(&self, language: Language) -> bool {
+        self.missing.get(&language).map_or(true, Vec::is_empty)
+    }
+}
+
+// 加载完成的字体链
+pub struct FontChain {
+    pub fonts: Vec<FontFile>,
+    pub problems: Vec<AssetProblem>, // 附带的字体缺少或损坏
+    pub warnings: Vec<AssetProblem>, // 系统缺少字体，与资源本身无关
+}
+
+impl FontChain {
+    // 读取字体链中的字体；都不能显示中文界面时再尝试系统字体
+    pub fn load(asset_dir: &Path) -> Self {
+        let texts = language_texts(asset_dir);
+        let mut chain = Self { fonts: Vec::new(), problems: Vec::new(), warnings: Vec::new() };
+        for path in FONT_CHAIN {
+            match FontFile::load(&asset_dir.join(path), &texts) {
+                Ok(font) => chain.fonts.push(font),
+                Err(error) => chain.problems.push(AssetProblem { path: path.to_string(), error }),
+            }
+        }
+
+        if chain.font_for(Language::ZhCn).is_none() {
+            let system_font = SYSTEM_CJK_FONTS
+                .iter()
+                .filter_map(|path| FontFile::load(Path::new(path), &texts).ok())
+                .find(|font| font.covers(Language::ZhCn));
+            match system_font {
+                Some(font) => chain.fonts.push(font),
+                None => chain.warnings.push(AssetProblem {
+                    path: "系统字体".to_string(),
+                    error: "没有可以显示全部中文界面的字体，界面使用英文".to_string(),
+                }),
+            }
+        }
+        if chain.font_for(Language::En).is_none() {
+            chain.problems.push(AssetProblem {
+                path: FONT_CHAIN[0].to_string(),
+                error: "没有可以显示全部英文界面的字体".to_string(),
+            });
+        }
+        chain
+    }
+
+    // 能显示该语言全部文字的第一个字体
+    pub fn font_for(&self, language: Language) -> Option<usize> {
+        self.fonts.iter().position(|font| font.covers(language))
+    }
+}
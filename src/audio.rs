@@ -1,5 +1,13 @@
 use bevy::prelude::*;
 
+// 音效文件（相对于资源目录）
+pub const WING_SOUND: &str = "audio/wing.ogg";
+pub const POINT_SOUND: &str = "audio/point.ogg";
+pub const HIT_SOUND: &str = "audio/hit.ogg";
+pub const DIE_SOUND: &str = "audio/die.ogg";
+pub const SWOOSH_SOUND: &str = "audio/swoosh.ogg";
+pub const SOUND_FILES: [&str; 5] = [WING_SOUND, POINT_SOUND, HIT_SOUND, DIE_SOUND, SWOOSH_SOUND];
+
 #[derive(Resource)]
 pub struct AudioAssets {
     pub wing: Handle<AudioSource>,
@@ -33,11 +41,11 @@ fn load_audio_assets(
     asset_server: Res<AssetServer>,
 ) {
     let audio_assets = AudioAssets {
-        wing: asset_server.load("audio/wing.ogg"),
-        point: asset_server.load("audio/point.ogg"),
-        hit: asset_server.load("audio/hit.ogg"),
-        die: asset_server.load("audio/die.ogg"),
-        swoosh: asset_server.load("audio/swoosh.ogg"),
+        wing: asset_server.load(WING_SOUND),
+        point: asset_server.load(POINT_SOUND),
+        hit: asset_server.load(HIT_SOUND),
+        die: asset_server.load(DIE_SOUND),
+        swoosh: asset_server.load(SWOOSH_SOUND),
     };
     
     commands.insert_resource(audio_assets);
@@ -0,0 +1,215 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use crate::components::{BirdCharacter, CharacterStats};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// 角色描述文件所在的目录（相对于资源目录）和扩展名
+pub const CHARACTER_DIR: &str = "characters";
+pub const CHARACTER_EXTENSION: &str = "character.json";
+
+// 角色描述 - 每个角色一个 assets/characters/*.character.json，新增角色不需要重新编译
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct CharacterManifest {
+    pub id: BirdCharacter, // 存档和回放中记录的角色标识，内置角色沿用旧版本的枚举名
+    #[serde(default)]
+    pub order: i32, // 菜单中的排列顺序，从小到大
+    pub name: String,
+    #[serde(default)]
+    pub localized_names: HashMap<String, String>, // 语言代码 -> 名称，例如 "en"、"zh-CN"
+    pub texture: String, // 静态贴图，用于菜单预览和碰撞遮罩；没有精灵表时也用于动画
+    #[serde(default)]
+    pub sheet: Option<SpriteSheet>, // 动画使用的精灵表
+    #[serde(default)]
+    pub clips: HashMap<ClipKind, AnimationClip>, // 各动画片段，缺少的片段使用 idle
+    pub scale: f32,
+    pub hitbox: [f32; 2], // 碰撞框尺寸，以贴图尺寸为单位，原点在贴图中心
+    pub stats: CharacterStats,
+}
+
+impl CharacterManifest {
+    // 指定语言的名称，没有翻译时使用默认名称
+    pub fn display_name(&self, language: &str) -> &str {
+        self.localized_names.get(language).unwrap_or(&self.name)
+    }
+
+    // 获取碰撞区域，单位与 PipeType::get_collision_segments 相同
+    pub fn collision_segments(&self) -> Vec<Rect> {
+        vec![Rect::from_center_size(Vec2::ZERO, Vec2::from(self.hitbox))]
+    }
+
+    pub fn profile(&self) -> CharacterProfile {
+        CharacterProfile {
+            stats: self.stats,
+            hitbox: self.hitbox,
+            scale: self.scale,
+        }
+    }
+}
+
+// 角色描述中影响模拟结果的部分，回放中记录，播放前与当前的角色描述比较
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CharacterProfile {
+    pub stats: CharacterStats,
+    pub hitbox: [f32; 2],
+    pub scale: f32,
+}
+
+// 按网格排列的精灵表，帧序号从左上角开始逐行递增
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpriteSheet {
+    pub texture: String,
+    pub tile_size: [u32; 2], // 每帧的像素尺寸
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl SpriteSheet {
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(UVec2::from(self.tile_size), self.columns, self.rows, None, None)
+    }
+}
+
+// 动画片段的种类，由小鸟的速度和撞击决定播放哪一个
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipKind {
+    Idle,  // 悬停、上升和滑翔
+    Flap,  // 拍打后播放一次
+    Fall,  // 机头完全朝下的俯冲
+    Death, // 撞击后播放一次并停在最后一帧
+}
+
+// 动画片段
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationClip {
+    pub frames: Vec<usize>, // 精灵表中的帧序号；没有精灵表时只能使用 0
+    pub frame_duration: f32, // 每帧显示的秒数
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(default)]
+    pub stretch: Vec<f32>, // 每帧的纵向拉伸（只影响显示，不影响碰撞），缺省为 1
+}
+
+impl AnimationClip {
+    // 播放 elapsed 秒后所在的帧：精灵表中的帧序号和纵向拉伸
+    // 非循环片段播放完后停在最后一帧
+    pub fn frame_at(&self, elapsed: f32) -> (usize, f32) {
+        let count = self.frames.len().max(self.stretch.len()).max(1);
+        let step = (elapsed / self.frame_duration.max(f32::EPSILON)) as usize;
+        let position = if self.looping { step % count } else { step.min(count - 1) };
+        (
+            self.frames.get(position).or(self.frames.last()).copied().unwrap_or(0),
+            self.stretch.get(position).copied().unwrap_or(1.0),
+        )
+    }
+
+    pub fn is_finished(&self, elapsed: f32) -> bool {
+        let count = self.frames.len().max(self.stretch.len()).max(1);
+        !self.looping && elapsed >= self.frame_duration * count as f32
+    }
+}
+
+// 角色描述文件的加载器
+#[derive(Default)]
+pub struct CharacterManifestLoader;
+
+impl AssetLoader for CharacterManifestLoader {
+    type Asset = CharacterManifest;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[CHARACTER_EXTENSION]
+    }
+}
+
+// 所有可选角色，按菜单顺序排列
+// 客户端在角色描述加载完成后插入；无头运行时需要插入（见 CharacterRegistry::load_from_dir）
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CharacterRegistry {
+    characters: Vec<CharacterManifest>,
+}
+
+impl CharacterRegistry {
+    pub fn new(mut characters: Vec<CharacterManifest>) -> Self {
+        characters.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.id.0.cmp(&b.id.0)));
+        characters.dedup_by(|a, b| a.id == b.id);
+        Self { characters }
+    }
+
+    // 直接从资源目录读取角色描述，供没有 AssetServer 的无头模拟使用
+    pub fn load_from_dir(asset_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut characters = Vec::new();
+        for entry in fs::read_dir(asset_dir.join(CHARACTER_DIR))? {
+            let path = entry?.path();
+            let is_manifest = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(&format!(".{}", CHARACTER_EXTENSION)));
+            if is_manifest {
+                let manifest = serde_json::from_str(&fs::read_to_string(&path)?)
+                    .map_err(|e| format!("角色描述格式错误 {}: {}", path.display(), e))?;
+                characters.push(manifest);
+            }
+        }
+        if characters.is_empty() {
+            return Err("没有找到任何角色描述".into());
+        }
+        Ok(Self::new(characters))
+    }
+
+    pub fn get(&self, id: &BirdCharacter) -> Option<&CharacterManifest> {
+        self.characters.iter().find(|character| character.id == *id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CharacterManifest> {
+        self.characters.iter()
+    }
+
+    pub fn ids(&self) -> Vec<BirdCharacter> {
+        self.characters.iter().map(|character| character.id.clone()).collect()
+    }
+
+    pub fn contains(&self, id: &BirdCharacter) -> bool {
+        self.get(id).is_some()
+    }
+
+    // 存档中的角色不存在时（例如角色描述被删除）使用第一个角色
+    pub fn resolve(&self, id: &BirdCharacter) -> BirdCharacter {
+        match self.characters.first() {
+            Some(first) if !self.contains(id) => first.id.clone(),
+            _ => id.clone(),
+        }
+    }
+
+    pub fn previous(&self, id: &BirdCharacter) -> BirdCharacter {
+        self.step(id, self.characters.len().saturating_sub(1))
+    }
+
+    pub fn next(&self, id: &BirdCharacter) -> BirdCharacter {
+        self.step(id, 1)
+    }
+
+    // 在菜单顺序中循环移动 offset 个位置
+    fn step(&self, id: &BirdCharacter, offset: usize) -> BirdCharacter {
+        let Some(index) = self.characters.iter().position(|character| character.id == *id) else {
+            return self.resolve(id);
+        };
+        self.characters[(index + offset) % self.characters.len()].id.clone()
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use crate::character::{CharacterManifest, CharacterManifestLoader, CharacterRegistry};
+use crate::collision::CollisionMasks;
+use crate::difficulty::{DifficultyCurve, DifficultyCurveLoader, DifficultyCurves};
+use crate::locale::Locale;
+use crate::replay::PendingReplay;
+use crate::resources::WindowFocus;
+use crate::settings::Settings;
+use crate::simulation::{simulation_running, SimulationSet};
+use crate::states::{GameState, PlayPhase};
+use crate::theme::SkyCycle;
+use crate::systems::*;
+
+// 客户端插件：相机、资源加载、精灵、菜单、界面、键盘输入和存档
+// 需要 DefaultPlugins 和 SimulationPlugin，并且 SaveManager 资源已插入
+pub struct ClientPlugin;
+
+impl Plugin for ClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::srgb(0.34, 0.75, 0.79)))
+            .insert_resource(SkyCycle::builtin())
+            .init_resource::<Locale>()
+            .init_resource::<Settings>()
+            .init_resource::<WindowFocus>()
+            .init_asset::<CharacterManifest>()
+            .init_asset_loader::<CharacterManifestLoader>()
+            .init_asset::<DifficultyCurve>()
+            .init_asset_loader::<DifficultyCurveLoader>()
+            .add_systems(Startup, (setup_camera, validate_assets, load_assets, set_window_icon))
+            .add_systems(
+                Update,
+                (
+                    (
+                        setup_menu_when_ready,
+                        menu_system,
+                        character_selection_system,
+                        difficulty_selection_system,
+                        start_pending_replay.run_if(resource_exists::<PendingReplay>),
+                    )
+                        .run_if(in_state(GameState::Menu).and(resource_exists::<DifficultyCurves>)),
+                    sky_theme_system,
+                    weather_system.run_if(
+                        in_state(PlayPhase::Ready)
+                            .or(in_state(PlayPhase::Running))
+                            .or(in_state(GameState::Replay)),
+                    ),
+                    bird_animation_system.run_if(
+                        in_state(PlayPhase::Ready)
+                            .or(in_state(PlayPhase::Running))
+                            .or(in_state(GameState::Replay))
+                            .or(in_state(GameState::Dying)),
+                    ),
+                    death_flash_system,
+                    slide_in_system.run_if(in_state(GameState::GameOver)),
+                    ready_bob_system.run_if(in_state(PlayPhase::Ready)),
+                    number_score_display
+                        .run_if(in_state(GameState::Playing).or(in_state(GameState::Replay))),
+                    track_window_focus,
+                    pause_system.after(track_window_focus).run_if(
+                        in_state(PlayPhase::Ready)
+                            .or(in_state(PlayPhase::Running))
+                            .or(in_state(PlayPhase::Countdown)),
+                    ),
+                    pause_menu_system.run_if(in_state(PlayPhase::Paused)),
+                    countdown_system.run_if(in_state(PlayPhase::Countdown)),
+                    leaderboard_system.run_if(in_state(GameState::Leaderboard)),
+                    settings_system.run_if(in_state(GameState::Settings)),
+                    apply_settings.run_if(resource_changed::<Settings>),
+                    restart_system,
+                    (
+                        build_character_registry.run_if(not(resource_exists::<CharacterRegistry>)),
+                        build_difficulty_curves.run_if(not(resource_exists::<DifficultyCurves>)),
+                    ),
+                    build_collision_masks.run_if(not(resource_exists::<CollisionMasks>)),
+                    sync_playfield_bounds,
+                ),
+            )
+            // 输入在固定步长循环之前采样，插值在循环之后应用
+            .add_systems(
+                RunFixedMainLoop,
+                (
+                    (
+                        restore_interpolated_transforms,
+                        bird_input_system
+                            .run_if(in_state(PlayPhase::Ready).or(in_state(PlayPhase::Running))),
+                    )
+                        .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                    interpolate_transforms.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+                ),
+            )
+            // 幽灵小鸟与真实小鸟在同一个固定步上拍打
+            .add_systems(
+                FixedUpdate,
+                ghost_flap_system
+                    .before(SimulationSet)
+                    .run_if(in_state(GameState::Playing).and(simulation_running)),
+            )
+            // 视差背景在模拟之后滚动，使用同一个管道速度
+            .add_systems(
+                FixedUpdate,
+                parallax_system.after(SimulationSet).run_if(simulation_running),
+            )
+            .add_systems(FixedFirst, record_previous_transforms)
+            .add_systems(FixedLast, record_current_transforms)
+            // 为模拟核心生成的实体附加精灵
+            .add_systems(PostUpdate, (attach_bird_sprite, attach_ground_sprite, attach_pipe_pair_visibility, attach_pipe_sprite))
+            .add_systems(OnExit(GameState::Menu), cleanup_menu)
+            // 场景在模拟插件的 cleanup_game 之后重建
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    (cleanup_scene, setup_background).chain(),
+                    (cleanup_ghost, select_ghost_run.before(setup_game), spawn_ghost).chain(),
+                )
+                    .after(cleanup_game),
+            )
+            .add_systems(OnExit(GameState::Playing), save_daily_result)
+            .add_systems(OnEnter(GameState::Replay), (cleanup_scene, setup_background).chain().after(cleanup_game))
+            .add_systems(OnEnter(GameState::Menu), (cleanup_scene, cleanup_ghost, cleanup_weather))
+            .add_systems(OnEnter(GameState::Dying), setup_death_flash)
+            .add_systems(OnEnter(PlayPhase::Ready), setup_ready_prompt)
+            .add_systems(OnExit(PlayPhase::Ready), cleanup_ready_prompt)
+            .add_systems(OnEnter(PlayPhase::Paused), setup_pause_overlay)
+            .add_systems(OnExit(PlayPhase::Paused), cleanup_pause_overlay)
+            .add_systems(OnEnter(PlayPhase::Countdown), setup_countdown)
+            .add_systems(OnExit(PlayPhase::Countdown), cleanup_countdown)
+            .add_systems(OnEnter(GameState::GameOver), (setup_game_over, save_game_data, save_replay))
+            .add_systems(OnExit(GameState::GameOver), cleanup_game_over)
+            .add_systems(OnEnter(GameState::Leaderboard), setup_leaderboard)
+            .add_systems(OnExit(GameState::Leaderboard), cleanup_leaderboard)
+            .add_systems(OnEnter(GameState::Settings), setup_settings)
+            .add_systems(OnExit(GameState::Settings), cleanup_settings);
+    }
+}
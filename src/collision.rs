@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use crate::character::{CharacterManifest, CharacterRegistry};
+use crate::components::{BirdCharacter, ColliderShape, PipeType};
+use bevy::asset::RenderAssetUsages;
+use bevy::image::{CompressedImageFormats, ImageSampler, ImageType};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// 透明度高于该值的像素视为实体
+const ALPHA_THRESHOLD: f32 = 0.5;
+
+// 透明度遮罩 - 记录纹理中每个像素是否不透明，用于像素级碰撞
+#[derive(Debug, Clone)]
+pub struct AlphaMask {
+    width: u32,
+    height: u32,
+    opaque: Vec<bool>, // 按行存储，第一行是纹理顶部
+}
+
+impl AlphaMask {
+    // 从已加载的图片构建遮罩，图片数据不可读时返回 None
+    pub fn from_image(image: &Image) -> Option<Self> {
+        let (width, height) = (image.width(), image.height());
+        let mut opaque = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let color = image.get_color_at(x, y).ok()?;
+                opaque.push(color.alpha() > ALPHA_THRESHOLD);
+            }
+        }
+        Some(Self { width, height, opaque })
+    }
+
+    // 整张纹理都不透明的遮罩，像素级检测退化为碰撞形状的 AABB 检测
+    pub fn solid(size: UVec2) -> Self {
+        Self {
+            width: size.x,
+            height: size.y,
+            opaque: vec![true; (size.x * size.y) as usize],
+        }
+    }
+
+    // 纹理尺寸（像素）
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    // 精灵局部坐标（原点在中心，y 轴向上）处的像素是否不透明
+    pub fn is_opaque_at(&self, local: Vec2) -> bool {
+        let x = (local.x + self.width as f32 / 2.0).floor();
+        let y = (self.height as f32 / 2.0 - local.y).floor();
+        if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+            return false;
+        }
+        self.opaque[y as usize * self.width as usize + x as usize]
+    }
+}
+
+// 所有角色和管道的碰撞遮罩
+// 客户端在图片加载完成后插入，无法读取的贴图退回到整张不透明的遮罩；插入之前模拟不会推进
+#[derive(Resource, Default)]
+pub struct CollisionMasks {
+    pub birds: HashMap<BirdCharacter, AlphaMask>,
+    pub pipes: HashMap<PipeType, AlphaMask>,
+}
+
+impl CollisionMasks {
+    pub fn get(&self, character: &BirdCharacter, pipe_type: PipeType) -> Option<(&AlphaMask, &AlphaMask)> {
+        Some((self.birds.get(character)?, self.pipes.get(&pipe_type)?))
+    }
+
+    // 根据角色贴图的实际尺寸生成碰撞形状
+    pub fn bird_shape(&self, character: &CharacterManifest) -> Option<ColliderShape> {
+        let mask = self.birds.get(&character.id)?;
+        Some(ColliderShape::from_texture(mask.size(), &character.collision_segments()))
+    }
+
+    // 根据管道贴图的实际尺寸生成碰撞形状
+    pub fn pipe_shape(&self, pipe_type: PipeType) -> Option<ColliderShape> {
+        let mask = self.pipes.get(&pipe_type)?;
+        Some(ColliderShape::from_texture(mask.size(), &pipe_type.get_collision_segments()))
+    }
+
+    // 直接从资源目录读取贴图构建遮罩，供没有 AssetServer 的无头模拟使用
+    pub fn load_from_dir(asset_dir: &Path, characters: &CharacterRegistry) -> Result<Self, Box<dyn std::error::Error>> {
+        let load = |path: &str| -> Result<AlphaMask, Box<dyn std::error::Error>> {
+            let bytes = fs::read(asset_dir.join(path))?;
+            let image = Image::from_buffer(
+                &bytes,
+                ImageType::Extension("png"),
+                CompressedImageFormats::NONE,
+                true,
+                ImageSampler::Default,
+                RenderAssetUsages::default(),
+            )?;
+            AlphaMask::from_image(&image).ok_or_else(|| format!("无法读取贴图像素: {}", path).into())
+        };
+
+        let mut masks = Self::default();
+        for character in characters.iter() {
+            masks.birds.insert(character.id.clone(), load(&character.texture)?);
+        }
+        for pipe_type in PipeType::all_types() {
+            masks.pipes.insert(pipe_type, load(pipe_type.get_texture_path())?);
+        }
+        Ok(masks)
+    }
+}
+
+// 计算精灵局部矩形经过变换（缩放、旋转、平移）后的世界包围盒
+pub fn world_rect(transform: &Transform, local: Rect) -> Rect {
+    [
+        local.min,
+        Vec2::new(local.max.x, local.min.y),
+        Vec2::new(local.min.x, local.max.y),
+        local.max,
+    ]
+    .into_iter()
+    .map(|corner| transform.transform_point(corner.extend(0.0)).truncate())
+    .fold(Rect::EMPTY, |bounds, point| bounds.union_point(point))
+}
+
+// 两个碰撞形状各段之间的重叠区域（世界坐标），不重叠时为空
+pub fn overlap_regions(
+    a_transform: &Transform,
+    a_shape: &ColliderShape,
+    b_transform: &Transform,
+    b_shape: &ColliderShape,
+) -> Vec<Rect> {
+    let mut regions = Vec::new();
+    for a in &a_shape.segments {
+        let a = world_rect(a_transform, *a);
+        for b in &b_shape.segments {
+            let region = a.intersect(world_rect(b_transform, *b));
+            if !region.is_empty() {
+                regions.push(region);
+            }
+        }
+    }
+    regions
+}
+
+// 像素级碰撞检测
+// 在碰撞形状的重叠区域内逐个世界像素检查两张遮罩是否同时不透明
+pub fn masks_overlap(
+    a_transform: &Transform,
+    a_mask: &AlphaMask,
+    b_transform: &Transform,
+    b_mask: &AlphaMask,
+    region: Rect,
+) -> bool {
+    let a_inverse = a_transform.compute_affine().inverse();
+    let b_inverse = b_transform.compute_affine().inverse();
+
+    let mut y = region.min.y + 0.5;
+    while y < region.max.y {
+        let mut x = region.min.x + 0.5;
+        while x < region.max.x {
+            let point = Vec3::new(x, y, 0.0);
+            if a_mask.is_opaque_at(a_inverse.transform_point3(point).truncate())
+                && b_mask.is_opaque_at(b_inverse.transform_point3(point).truncate())
+            {
+                return true;
+            }
+            x += 1.0;
+        }
+        y += 1.0;
+    }
+    false
+}
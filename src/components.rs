@@ -1,15 +1,25 @@
 use bevy::prelude::*;
+use crate::character::{AnimationClip, ClipKind};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-// 小鸟角色枚举 - 支持所有6个角色
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum BirdCharacter {
-    YellowBird,
-    RedBird,
-    BlueBird,
-    WuSaQi,     // 乌撒奇
-    JiYi,       // 吉伊
-    XiaoBa,     // 小八
+// 小鸟角色标识 - 对应 assets/characters 下角色描述的 id
+// 序列化为字符串，与旧版本存档中的枚举名兼容（例如 "YellowBird"、"XiaoBa"）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BirdCharacter(pub String);
+
+impl BirdCharacter {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+}
+
+// 默认角色：经典的黄色小鸟
+impl Default for BirdCharacter {
+    fn default() -> Self {
+        Self::new("YellowBird")
+    }
 }
 
 // 小鸟组件
@@ -18,11 +28,41 @@ pub struct Bird {
     pub character: BirdCharacter,
 }
 
-// 管道类型枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+// 幽灵小鸟组件 - 重放个人最佳回放的轨迹，不参与碰撞和计分
+#[derive(Component)]
+pub struct GhostBird {
+    pub flaps: Vec<u64>,
+    pub next_flap: usize,
+    pub end_tick: u64, // 录制时撞击的固定步，到达后幽灵消失
+}
+
+// 管道类型枚举 - 传统管道和节日装饰障碍物
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PipeType {
     Green,
     Red,
+    Lantern2,    // 灯笼2
+    Lantern3,    // 灯笼3
+    Gourd3,      // 葫芦串3
+    Gourd5,      // 葫芦串5
+    ColorGourd3, // 彩葫芦串3
+    ColorGourd4, // 彩葫芦串4
+}
+
+// 障碍物主题 - 决定管道生成时可选的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PipeTheme {
+    #[default]
+    Mixed,    // 传统管道和节日装饰混合出现
+    Festival, // 只出现灯笼和葫芦串
+}
+
+// 顶部边界的行为
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CeilingMode {
+    #[default]
+    Kill,  // 碰到顶部即撞击
+    Clamp, // 顶部阻挡小鸟，贴着顶部滑行
 }
 
 // 管道组件
@@ -31,15 +71,73 @@ pub struct Pipe {
     pub pipe_type: PipeType,
 }
 
+// 障碍物运动类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeMotionKind {
+    Static,    // 静止
+    Oscillate, // 整个通道上下摆动
+    Breathe,   // 通道周期性合拢再张开
+    Drift,     // 通道斜向漂移
+}
+
+// 障碍物运动模式及其参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PipeMotionPattern {
+    Static,
+    Oscillate { amplitude: f32, period: f32 },
+    Breathe { amplitude: f32, period: f32 },
+    Drift { speed: f32, range: f32 }, // 通道中心在 ±range 之间往返
+}
+
+// 障碍物运动组件 - 挂在管道对上，描述通道随时间的变化
+#[derive(Component, Debug, Clone)]
+pub struct PipeMotion {
+    pub pattern: PipeMotionPattern,
+    pub gap_y: f32,    // 生成时的通道中心
+    pub half_gap: f32, // 生成时通道高度的一半
+    pub elapsed: f32,  // 生成后经过的模拟时间
+}
+
+impl PipeMotion {
+    // 当前时刻的通道中心和通道半高
+    pub fn gap(&self) -> (f32, f32) {
+        self.pattern.gap_at(self.gap_y, self.half_gap, self.elapsed)
+    }
+}
+
+// 管道对 - 上下两根管道和计分门的父实体，负责整体滚动和通道运动
+#[derive(Component)]
+pub struct PipePair;
+
+// 管道在管道对中的位置
+#[derive(Component)]
+pub struct PipeOffset {
+    pub side: f32,          // 上管道为 1，下管道为 -1
+    pub center_offset: f32, // 管道中心到通道边缘的距离
+}
+
+// 计分门 - 管道对的子实体，小鸟越过后标记为已计分，每对管道只计一分
+#[derive(Component, Default)]
+pub struct ScoreGate {
+    pub scored: bool,
+}
+
 // 环境组件
+// 地面 - 变换的 y 是地面顶部，小鸟碰到即撞击，撞击后落在上面
 #[derive(Component)]
 pub struct Ground;
 
+// 视差背景层中的一个元素，layer 为 GameAssets::parallax_layers 中的序号
 #[derive(Component)]
-pub struct Cloud;
+pub struct Parallax {
+    pub layer: usize,
+}
 
+// 天气粒子，只影响显示
 #[derive(Component)]
-pub struct Mountain;
+pub struct WeatherParticle {
+    pub velocity: Vec2,
+}
 
 // 物理组件 - 移除未使用的 x 字段
 #[derive(Component)]
@@ -47,23 +145,94 @@ pub struct Velocity {
     pub y: f32,  // 只保留 y 轴速度，因为 x 轴未使用
 }
 
-#[derive(Component)]
-pub struct Gravity(pub f32);
+// 角色属性 - 决定小鸟的手感，挂在小鸟和幽灵上，由角色描述提供
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CharacterStats {
+    pub gravity: f32,           // 重力加速度
+    pub flap_impulse: f32,      // 拍打后的上升速度
+    pub terminal_velocity: f32, // 最大下落速度
+    pub tilt_velocity: f32,     // 达到最大倾角时的竖直速度，越小机头转得越快
+}
+
+// 小鸟的最大倾角（弧度）
+pub const BIRD_MAX_TILT: f32 = 0.5;
 
 #[derive(Component)]
 pub struct Collider;
 
+// 碰撞形状 - 生成时根据贴图实际尺寸计算
+// 每段是精灵局部坐标下的矩形（贴图像素，原点在中心，y 轴向上），世界位置由变换决定
+#[derive(Component, Debug, Clone)]
+pub struct ColliderShape {
+    pub segments: Vec<Rect>,
+}
+
+impl ColliderShape {
+    // 把以贴图尺寸为单位的碰撞段换算为贴图像素
+    pub fn from_texture(texture_size: Vec2, segments: &[Rect]) -> Self {
+        Self {
+            segments: segments
+                .iter()
+                .map(|segment| Rect {
+                    min: segment.min * texture_size,
+                    max: segment.max * texture_size,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Scrolling {
     pub speed: f32,
+    pub half_width: f32, // 贴图的半宽（世界坐标），整个越过屏幕左边缘后才移除
 }
 
-// 动画组件
+// 渲染插值组件 - 记录最近两个固定步长的变换，渲染时在两者之间插值
 #[derive(Component)]
-pub struct WingAnimation {
-    pub timer: Timer,
-    pub current_frame: usize,
-    pub frames: Vec<Handle<Image>>,
+pub struct TransformInterpolation {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
+impl TransformInterpolation {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+        }
+    }
+}
+
+// 小鸟动画组件 - 按小鸟的速度和撞击在角色的动画片段之间切换，只影响显示
+#[derive(Component)]
+pub struct BirdAnimation {
+    pub clips: HashMap<ClipKind, AnimationClip>,
+    pub clip: ClipKind,
+    pub elapsed: f32,       // 当前片段已播放的秒数
+    pub last_velocity: f32, // 上一帧的竖直速度，速度突然增大说明刚刚拍打
+}
+
+impl BirdAnimation {
+    pub fn new(clips: HashMap<ClipKind, AnimationClip>) -> Self {
+        Self {
+            clips,
+            clip: ClipKind::Idle,
+            elapsed: 0.0,
+            last_velocity: 0.0,
+        }
+    }
+
+    // 缺少的片段使用 idle
+    pub fn current_clip(&self) -> Option<&AnimationClip> {
+        self.clips.get(&self.clip).or_else(|| self.clips.get(&ClipKind::Idle))
+    }
+
+    // 从头播放另一个片段
+    pub fn play(&mut self, clip: ClipKind) {
+        self.clip = clip;
+        self.elapsed = 0.0;
+    }
 }
 
 // UI组件
@@ -74,14 +243,45 @@ pub struct ScoreText;
 pub struct MenuText;
 
 #[derive(Component)]
-pub struct CharacterPreview;
+pub struct CharacterPanel;
+
+// 菜单中的设置入口和当前难度，切换难度时更新
+#[derive(Component)]
+pub struct DifficultyText;
+
+#[derive(Component)]
+pub struct SettingsText;
+
+// 设置界面中的一行，值为 SettingsItem::ALL 中的序号
+#[derive(Component)]
+pub struct SettingsRow(pub usize);
 
 #[derive(Component)]
 pub struct GameOverText;
 
+// 从下方滑入目标位置（原点）的界面，offset 为起始时低于目标位置的距离
+#[derive(Component)]
+pub struct SlideIn {
+    pub offset: f32,
+    pub timer: Timer,
+}
+
+// 撞击时的全屏闪光，逐渐淡出后消失
+#[derive(Component)]
+pub struct DeathFlash(pub Timer);
+
 #[derive(Component)]
 pub struct ScoreDigit;
 
+#[derive(Component)]
+pub struct ReadyPrompt;
+
+#[derive(Component)]
+pub struct PauseOverlay;
+
+#[derive(Component)]
+pub struct CountdownText;
+
 #[derive(Component)]
 pub struct LeaderboardText;
 
@@ -96,156 +296,185 @@ impl PipeType {
         match self {
             PipeType::Green => "pipes/pipe-green.png",
             PipeType::Red => "pipes/pipe-red.png",
+            PipeType::Lantern2 => "pipes/灯笼2.png",
+            PipeType::Lantern3 => "pipes/灯笼3.png",
+            PipeType::Gourd3 => "pipes/葫芦串3.png",
+            PipeType::Gourd5 => "pipes/葫芦串5.png",
+            PipeType::ColorGourd3 => "pipes/彩葫芦串3.png",
+            PipeType::ColorGourd4 => "pipes/彩葫芦串4.png",
         }
     }
     
+    // 节日装饰的图片分辨率较高，缩放到主体部分足以从通道边缘延伸到屏幕边缘
     pub fn get_scale(&self) -> f32 {
         match self {
             PipeType::Green | PipeType::Red => 1.0,
-        }
-    }
-    
-    pub fn get_collision_bounds(&self) -> (f32, f32) {
-        // 返回 (width_factor, height_factor) 相对于原始尺寸的比例
-        match self {
-            PipeType::Green | PipeType::Red => (0.8, 0.9),
+            PipeType::Lantern2 => 0.72,
+            PipeType::Lantern3 => 0.49,
+            PipeType::Gourd3 => 0.68,
+            PipeType::Gourd5 => 0.43,
+            PipeType::ColorGourd3 => 0.67,
+            PipeType::ColorGourd4 => 0.53,
         }
     }
     
     // 获取碰撞区域
-    pub fn get_collision_segments(&self) -> Vec<(f32, f32, f32, f32)> {
-        // 返回碰撞矩形段：(x_offset, y_offset, width_factor, height_factor)
+    // 以贴图尺寸为单位：原点在贴图中心，(-0.5, -0.5) 为左下角，(0.5, 0.5) 为右上角
+    // 节日装饰分为主体和末端的细杆（流苏），两段之外的留白不参与碰撞
+    pub fn get_collision_segments(&self) -> Vec<Rect> {
+        let segment = |x: f32, y: f32, width: f32, height: f32| {
+            Rect::from_center_size(Vec2::new(x, y), Vec2::new(width, height))
+        };
         match self {
-            PipeType::Green | PipeType::Red => {
-                // 传统管道使用单一矩形
-                vec![(0.0, 0.0, 0.8, 0.9)]
-            },
-        }
-    }
-    
-    pub fn get_collision_offset(&self) -> (f32, f32) {
-        // 返回碰撞中心相对于图片中心的偏移
-        match self {
-            PipeType::Green | PipeType::Red => (0.0, 0.0),
-        }
-    }
-    
-    // 判断是否使用精确碰撞检测
-    pub fn use_precise_collision(&self) -> bool {
-        match self {
-            PipeType::Green | PipeType::Red => false, // 传统管道使用简单AABB
+            // 传统管道使用单一矩形
+            PipeType::Green | PipeType::Red => vec![segment(0.0, 0.0, 0.8, 0.9)],
+            PipeType::Lantern2 => vec![
+                segment(0.0, 0.083, 0.96, 0.794),
+                segment(0.03, -0.407, 0.18, 0.186),
+            ],
+            PipeType::Lantern3 => vec![
+                segment(0.0, 0.054, 0.95, 0.848),
+                segment(0.056, -0.435, 0.17, 0.13),
+            ],
+            PipeType::Gourd3 => vec![
+                segment(0.0, 0.145, 0.93, 0.679),
+                segment(-0.014, -0.348, 0.14, 0.305),
+            ],
+            PipeType::Gourd5 => vec![
+                segment(0.0, 0.084, 0.91, 0.797),
+                segment(0.006, -0.408, 0.14, 0.185),
+            ],
+            PipeType::ColorGourd3 => vec![
+                segment(0.0, 0.137, 0.94, 0.695),
+                segment(0.01, -0.356, 0.14, 0.289),
+            ],
+            PipeType::ColorGourd4 => vec![
+                segment(0.0, 0.117, 0.95, 0.727),
+                segment(0.012, -0.369, 0.14, 0.245),
+            ],
         }
     }
     
-    pub fn all_types() -> [PipeType; 2] {
+    pub const fn all_types() -> [PipeType; 8] {
         [
             PipeType::Green,
             PipeType::Red,
+            PipeType::Lantern2,
+            PipeType::Lantern3,
+            PipeType::Gourd3,
+            PipeType::Gourd5,
+            PipeType::ColorGourd3,
+            PipeType::ColorGourd4,
+        ]
+    }
+    
+    pub const fn festival_types() -> [PipeType; 6] {
+        [
+            PipeType::Lantern2,
+            PipeType::Lantern3,
+            PipeType::Gourd3,
+            PipeType::Gourd5,
+            PipeType::ColorGourd3,
+            PipeType::ColorGourd4,
         ]
     }
 }
 
-impl BirdCharacter {
-    pub fn get_texture_path(&self) -> &'static str {
-        match self {
-            BirdCharacter::YellowBird => "birds/yellowbird-midflap.png",
-            BirdCharacter::RedBird => "birds/redbird-midflap.png",
-            BirdCharacter::BlueBird => "birds/bluebird-midflap.png",
-            BirdCharacter::WuSaQi => "birds/乌撒奇.png",
-            BirdCharacter::JiYi => "birds/吉伊.png",
-            BirdCharacter::XiaoBa => "birds/小八.png",
-        }
+impl PipeMotionKind {
+    pub fn all_kinds() -> [PipeMotionKind; 4] {
+        [
+            PipeMotionKind::Static,
+            PipeMotionKind::Oscillate,
+            PipeMotionKind::Breathe,
+            PipeMotionKind::Drift,
+        ]
     }
     
-    pub fn get_name(&self) -> &'static str {
+    // 难度权重：越大越难，解锁越晚、出现得越少
+    pub fn difficulty_weight(&self) -> u32 {
         match self {
-            BirdCharacter::YellowBird => "Yellow Bird",
-            BirdCharacter::RedBird => "Red Bird",
-            BirdCharacter::BlueBird => "Blue Bird",
-            BirdCharacter::WuSaQi => "乌撒奇",
-            BirdCharacter::JiYi => "吉伊",
-            BirdCharacter::XiaoBa => "小八",
+            PipeMotionKind::Static => 0,
+            PipeMotionKind::Oscillate => 1,
+            PipeMotionKind::Breathe => 2,
+            PipeMotionKind::Drift => 3,
         }
     }
     
-    pub fn get_scale(&self) -> f32 {
-        match self {
-            // 英文小鸟使用更大的缩放
-            BirdCharacter::YellowBird | BirdCharacter::RedBird | BirdCharacter::BlueBird => 2.0,
-            // 中文角色使用更小的缩放
-            BirdCharacter::WuSaQi | BirdCharacter::JiYi | BirdCharacter::XiaoBa => 0.3,
+    // 被选中的相对概率，max_difficulty 由难度曲线给出，难度不超过它的模式才会出现
+    pub fn spawn_weight(&self, max_difficulty: f32) -> f32 {
+        let difficulty = self.difficulty_weight();
+        if (difficulty as f32) > max_difficulty {
+            0.0
+        } else {
+            1.0 / (difficulty + 1) as f32
         }
     }
-    
-    pub fn previous(&self) -> BirdCharacter {
-        match self {
-            BirdCharacter::YellowBird => BirdCharacter::XiaoBa,
-            BirdCharacter::RedBird => BirdCharacter::YellowBird,
-            BirdCharacter::BlueBird => BirdCharacter::RedBird,
-            BirdCharacter::WuSaQi => BirdCharacter::BlueBird,
-            BirdCharacter::JiYi => BirdCharacter::WuSaQi,
-            BirdCharacter::XiaoBa => BirdCharacter::JiYi,
+}
+
+impl PipeMotionPattern {
+    // 运动 elapsed 秒后的通道中心和通道半高
+    pub fn gap_at(&self, gap_y: f32, half_gap: f32, elapsed: f32) -> (f32, f32) {
+        match *self {
+            PipeMotionPattern::Static => (gap_y, half_gap),
+            PipeMotionPattern::Oscillate { amplitude, period } => {
+                let phase = elapsed / period * std::f32::consts::TAU;
+                (gap_y + amplitude * phase.sin(), half_gap)
+            }
+            PipeMotionPattern::Breathe { amplitude, period } => {
+                // 从完全张开开始，只向内合拢，不会超出生成时的通道
+                let phase = elapsed / period * std::f32::consts::TAU;
+                (gap_y, half_gap - amplitude * (1.0 - phase.cos()) / 2.0)
+            }
+            PipeMotionPattern::Drift { speed, range } => {
+                // 三角波：到达边界后折返
+                let span = range * 2.0;
+                let position = (gap_y + range + speed * elapsed).rem_euclid(span * 2.0);
+                let reflected = if position < span { position } else { span * 2.0 - position };
+                (reflected - range, half_gap)
+            }
         }
     }
-    
-    pub fn next(&self) -> BirdCharacter {
+}
+
+impl CeilingMode {
+    // 界面文字的键，见 assets/locales
+    pub fn locale_key(&self) -> &'static str {
         match self {
-            BirdCharacter::YellowBird => BirdCharacter::RedBird,
-            BirdCharacter::RedBird => BirdCharacter::BlueBird,
-            BirdCharacter::BlueBird => BirdCharacter::WuSaQi,
-            BirdCharacter::WuSaQi => BirdCharacter::JiYi,
-            BirdCharacter::JiYi => BirdCharacter::XiaoBa,
-            BirdCharacter::XiaoBa => BirdCharacter::YellowBird,
+            CeilingMode::Kill => "ceiling.kill",
+            CeilingMode::Clamp => "ceiling.clamp",
         }
     }
     
-    pub fn all_characters() -> [BirdCharacter; 6] {
-        [
-            BirdCharacter::YellowBird,
-            BirdCharacter::RedBird,
-            BirdCharacter::BlueBird,
-            BirdCharacter::WuSaQi,
-            BirdCharacter::JiYi,
-            BirdCharacter::XiaoBa,
-        ]
+    pub fn next(&self) -> CeilingMode {
+        match self {
+            CeilingMode::Kill => CeilingMode::Clamp,
+            CeilingMode::Clamp => CeilingMode::Kill,
+        }
     }
-    
-    pub fn get_collision_radius(&self) -> f32 {
+}
+
+impl PipeTheme {
+    pub fn locale_key(&self) -> &'static str {
         match self {
-            // 英文小鸟使用标准碰撞半径
-            BirdCharacter::YellowBird | BirdCharacter::RedBird | BirdCharacter::BlueBird => 12.0,
-            // 中文角色可能需要不同的碰撞半径
-            BirdCharacter::WuSaQi | BirdCharacter::JiYi | BirdCharacter::XiaoBa => 10.0,
+            PipeTheme::Mixed => "pipe_theme.mixed",
+            PipeTheme::Festival => "pipe_theme.festival",
         }
     }
     
-    // 获取动画帧路径
-    pub fn get_animation_frames(&self) -> Vec<&'static str> {
+    pub fn next(&self) -> PipeTheme {
         match self {
-            BirdCharacter::YellowBird => vec![
-                "birds/yellowbird-downflap.png",
-                "birds/yellowbird-midflap.png",
-                "birds/yellowbird-upflap.png",
-            ],
-            BirdCharacter::RedBird => vec![
-                "birds/redbird-downflap.png",
-                "birds/redbird-midflap.png",
-                "birds/redbird-upflap.png",
-            ],
-            BirdCharacter::BlueBird => vec![
-                "birds/bluebird-downflap.png",
-                "birds/bluebird-midflap.png",
-                "birds/bluebird-upflap.png",
-            ],
-            // 中文角色不使用动画，返回单帧
-            BirdCharacter::WuSaQi => vec!["birds/乌撒奇.png"],
-            BirdCharacter::JiYi => vec!["birds/吉伊.png"],
-            BirdCharacter::XiaoBa => vec!["birds/小八.png"],
+            PipeTheme::Mixed => PipeTheme::Festival,
+            PipeTheme::Festival => PipeTheme::Mixed,
         }
     }
     
-    // 检查是否支持动画
-    pub fn has_animation(&self) -> bool {
-        matches!(self, BirdCharacter::YellowBird | BirdCharacter::RedBird | BirdCharacter::BlueBird)
+    // 该主题下管道生成时可选的类型
+    pub fn pipe_types(&self) -> &'static [PipeType] {
+        const MIXED: [PipeType; 8] = PipeType::all_types();
+        const FESTIVAL: [PipeType; 6] = PipeType::festival_types();
+        match self {
+            PipeTheme::Mixed => &MIXED,
+            PipeTheme::Festival => &FESTIVAL,
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use crate::collision::world_rect;
+use crate::components::{ColliderShape, PipeMotionPattern, BIRD_MAX_TILT};
+
+// 管道对生成时的横坐标
+pub const PIPE_SPAWN_X: f32 = 500.0;
+
+// 小鸟的横坐标
+pub const BIRD_X: f32 = -200.0;
+
+// 小鸟中心与通道边缘之间额外保留的距离
+const CORRIDOR_MARGIN: f32 = 4.0;
+
+// 弧顶高度范围至少保留的宽度，修正后的通道不会刚好只能从一个高度穿过
+const APEX_SLACK: f32 = 8.0;
+
+// 两个通道之间的升降距离只使用理论值的一部分，并额外扣除固定余量，给速度变化和操作误差留出空间
+const REACH_SAFETY: f32 = 0.8;
+const REACH_MARGIN: f32 = 4.0;
+
+// 估算安全通道时在穿越期间采样的次数
+const CORRIDOR_SAMPLES: usize = 16;
+
+// 可达性约束用到的物理参数，全部取自角色属性和实际的游戏配置
+#[derive(Debug, Clone, Copy)]
+pub struct CoursePhysics {
+    pub gravity: f32,
+    pub jump_force: f32,
+    pub terminal_velocity: f32,  // 最大下落速度
+    pub timestep: f32,           // 固定步长（秒）
+    pub pipe_speed: f32,
+    pub spawn_interval: f32,     // 管道生成间隔（秒）
+    pub bird_half_extents: Vec2, // 小鸟碰撞框在任意倾角下的半宽和半高
+}
+
+// 一对管道的通道描述
+#[derive(Debug, Clone, Copy)]
+pub struct GapPlan {
+    pub pattern: PipeMotionPattern,
+    pub gap_y: f32,
+    pub half_gap: f32,
+    pub half_width: f32, // 障碍物碰撞区域的半宽（世界坐标）
+    pub spawn_time: f32, // 生成时的本局时间（秒）
+}
+
+// 纵向区间，用于小鸟中心可以停留的通道和弧顶可以所在的高度
+#[derive(Debug, Clone, Copy)]
+pub struct Corridor {
+    pub low: f32,
+    pub high: f32,
+}
+
+impl Corridor {
+    fn intersect(self, other: Corridor) -> Option<Corridor> {
+        let corridor = Corridor {
+            low: self.low.max(other.low),
+            high: self.high.min(other.high),
+        };
+        (corridor.low <= corridor.high).then_some(corridor)
+    }
+
+    // 把区间中心平移到 target 内所需的最小距离
+    fn offset_into(self, target: Corridor) -> f32 {
+        let center = (self.low + self.high) / 2.0;
+        center.clamp(target.low, target.high) - center
+    }
+}
+
+// 小鸟按抛物线弧依次穿过每对管道：弧顶位于穿越的正中间，穿越期间不拍打；
+// 穿越时间超过一次拍打的弧长时改为在通道内连续盘旋。
+// 生成器记录每对管道可用的弧顶高度范围，下一对管道的弧顶必须能从这个范围到达。
+impl CoursePhysics {
+    // 小鸟碰撞框在任意倾角下相对中心的最大半宽和半高
+    pub fn bird_half_extents(shape: &ColliderShape, scale: Vec3) -> Vec2 {
+        let mut extents = Vec2::ZERO;
+        for step in -2..=2 {
+            let angle = BIRD_MAX_TILT * step as f32 / 2.0;
+            let transform = Transform::from_rotation(Quat::from_rotation_z(angle)).with_scale(scale);
+            for segment in &shape.segments {
+                let rect = world_rect(&transform, *segment);
+                extents = extents.max(rect.min.abs()).max(rect.max.abs());
+            }
+        }
+        extents
+    }
+
+    // 障碍物碰撞区域相对中心的最大半宽
+    pub fn obstacle_half_width(shape: &ColliderShape, scale: Vec3) -> f32 {
+        let transform = Transform::from_scale(scale);
+        shape
+            .segments
+            .iter()
+            .map(|segment| world_rect(&transform, *segment))
+            .fold(0.0, |half_width: f32, rect| half_width.max(rect.min.x.abs()).max(rect.max.x.abs()))
+    }
+
+    // 一次拍打从起跳到弧顶的时间和高度，按 bird_physics_system 的固定步积分
+    fn flap_arc(&self) -> (f32, f32) {
+        let (mut velocity, mut height, mut time) = (self.jump_force, 0.0, 0.0);
+        loop {
+            velocity -= self.gravity * self.timestep;
+            if velocity <= 0.0 {
+                return (time, height);
+            }
+            height += velocity * self.timestep;
+            time += self.timestep;
+        }
+    }
+
+    // 每个固定步都拍打时的上升速度
+    fn climb_rate(&self) -> f32 {
+        (self.jump_force - self.gravity * self.timestep).max(0.0)
+    }
+
+    // 从弧顶开始下落 duration 秒的距离，达到最大下落速度后匀速
+    fn fall(&self, duration: f32) -> f32 {
+        let accelerating = (self.terminal_velocity / self.gravity).min(duration);
+        self.gravity * accelerating * accelerating / 2.0 + self.terminal_velocity * (duration - accelerating)
+    }
+
+    // 穿越障碍物所需的时间：碰撞区域从开始到结束与小鸟重叠
+    fn traversal_time(&self, half_width: f32) -> f32 {
+        2.0 * (half_width + self.bird_half_extents.x) / self.pipe_speed
+    }
+
+    // 穿越期间的安全通道：各时刻通道的交集，去掉小鸟的半高和余量
+    pub fn corridor(&self, plan: &GapPlan) -> Option<Corridor> {
+        let traversal = self.traversal_time(plan.half_width);
+        let enter = (PIPE_SPAWN_X - BIRD_X) / self.pipe_speed - traversal / 2.0;
+        let mut corridor = Corridor { low: f32::MIN, high: f32::MAX };
+        for i in 0..=CORRIDOR_SAMPLES {
+            let time = enter + traversal * i as f32 / CORRIDOR_SAMPLES as f32;
+            let (gap_y, half_gap) = plan.pattern.gap_at(plan.gap_y, plan.half_gap, time);
+            corridor = corridor.intersect(Corridor { low: gap_y - half_gap, high: gap_y + half_gap })?;
+        }
+
+        let inset = self.bird_half_extents.y + CORRIDOR_MARGIN;
+        let corridor = Corridor { low: corridor.low + inset, high: corridor.high - inset };
+        (corridor.low <= corridor.high).then_some(corridor)
+    }
+
+    // 穿越期间弧顶以下需要的高度；盘旋时为一次拍打的弧高
+    fn arc_drop(&self, half_width: f32, hover: bool) -> f32 {
+        let (apex_time, apex_height) = self.flap_arc();
+        let half_traversal = self.traversal_time(half_width) / 2.0;
+        if hover || half_traversal > apex_time {
+            apex_height
+        } else {
+            self.fall(half_traversal)
+        }
+    }
+
+    // 穿越障碍物时弧顶允许的高度范围
+    fn apex_range(&self, plan: &GapPlan, hover: bool) -> Option<Corridor> {
+        let corridor = self.corridor(plan)?;
+        let low = corridor.low + self.arc_drop(plan.half_width, hover);
+        (low <= corridor.high).then_some(Corridor { low, high: corridor.high })
+    }
+
+    // 两对管道挨得太近，小鸟来不及在中间开始新的弧，只能保持盘旋连续穿过
+    fn close_together(&self, half_width: f32, interval: f32) -> bool {
+        let (apex_time, _) = self.flap_arc();
+        interval < apex_time + self.traversal_time(half_width) / 2.0
+    }
+
+    // 通道至少需要的半高
+    pub fn required_half_gap(&self, half_width: f32, hover: bool) -> f32 {
+        self.bird_half_extents.y + CORRIDOR_MARGIN + (self.arc_drop(half_width, hover) + APEX_SLACK) / 2.0
+    }
+
+    // 离开上一对管道后，下一对管道的弧顶能够到达的高度范围
+    fn reach_range(&self, previous: &GapPlan, previous_apex: Corridor, next: &GapPlan) -> Option<Corridor> {
+        let interval = next.spawn_time - previous.spawn_time;
+        if self.close_together(previous.half_width, interval) {
+            return self.apex_range(previous, true)?.intersect(previous_apex);
+        }
+
+        // 下降到底：离开弧顶后一直不拍打，最后一次拍打正好形成下一个弧顶
+        // 上升到顶：穿过上一对管道后每个固定步都拍打，最后一次拍打同样形成弧顶
+        let (apex_time, apex_height) = self.flap_arc();
+        let half_traversal = self.traversal_time(previous.half_width) / 2.0;
+        let drop = self.fall(interval - apex_time) - apex_height;
+        let rise = apex_height - self.fall(half_traversal)
+            + self.climb_rate() * (interval - apex_time - half_traversal);
+        let low = previous_apex.low - conservative(drop);
+        let high = previous_apex.high + conservative(rise);
+        // 间隔很短时保守估计的升降距离可能都是负数，此时只能保持在中间的高度
+        let middle = (low + high) / 2.0;
+        Some(Corridor { low: low.min(middle), high: high.max(middle) })
+    }
+}
+
+// 按安全系数和余量缩小升降距离，结果总是比理论值更难达到
+fn conservative(distance: f32) -> f32 {
+    distance - distance.abs() * (1.0 - REACH_SAFETY) - REACH_MARGIN
+}
+
+// 可达性约束下的通道生成器，记录上一对管道的通道和可用的弧顶高度
+#[derive(Resource, Default)]
+pub struct CoursePlanner {
+    previous: Option<(GapPlan, Corridor)>,
+}
+
+impl CoursePlanner {
+    // 修正随机生成的通道，使其在物理上可以从上一对管道到达
+    // 依次尝试：保持原样、平移通道、改为静止通道，仍然无法到达时加宽通道；修正过程不消耗随机数
+    pub fn place(&mut self, physics: &CoursePhysics, candidate: GapPlan, gap_center_range: f32) -> GapPlan {
+        // 与前后任一对管道挨得太近时需要能在通道内盘旋
+        let hover = physics.close_together(candidate.half_width, physics.spawn_interval)
+            || self.previous.is_some_and(|(previous, _)| {
+                physics.close_together(previous.half_width, candidate.spawn_time - previous.spawn_time)
+            });
+
+        // 通道至少要能容纳穿越障碍物所需的抛物线弧
+        let candidate = GapPlan {
+            half_gap: candidate.half_gap.max(physics.required_half_gap(candidate.half_width, hover)),
+            ..candidate
+        };
+        let still = GapPlan { pattern: PipeMotionPattern::Static, ..candidate };
+
+        let reach = self
+            .previous
+            .and_then(|(previous, previous_apex)| physics.reach_range(&previous, previous_apex, &candidate));
+        let Some(reach) = reach else {
+            // 第一对管道只需要本身能够穿过
+            let plan = if physics.apex_range(&candidate, hover).is_some() { candidate } else { still };
+            return self.accept(physics, plan, hover, None);
+        };
+
+        // 保持原样，或平移到可达范围内，运动模式不变；运动后无法穿过时改为静止通道
+        for plan in [candidate, still] {
+            let Some(apex) = physics.apex_range(&plan, hover) else {
+                continue;
+            };
+            let shifted = GapPlan { gap_y: plan.gap_y + apex.offset_into(reach), ..plan };
+            let reachable = physics
+                .apex_range(&shifted, hover)
+                .and_then(|apex| apex.intersect(reach));
+            if let (true, Some(apex)) = (stays_in_range(&shifted, gap_center_range), reachable) {
+                self.previous = Some((shifted, apex));
+                return shifted;
+            }
+        }
+
+        // 可达范围超出通道中心的活动范围时，平移到活动范围的边缘，
+        // 再向两侧加宽通道，直到弧顶高度范围与可达范围重叠；加宽后障碍物向外移动，仍能覆盖到屏幕边缘
+        let offset = physics
+            .apex_range(&still, hover)
+            .map_or(0.0, |apex| apex.offset_into(reach));
+        let gap_y = (still.gap_y + offset).clamp(-gap_center_range, gap_center_range);
+        let clamped = GapPlan { gap_y, ..still };
+        let shortfall = physics
+            .apex_range(&clamped, hover)
+            .map_or(0.0, |apex| (apex.low - reach.high).max(reach.low - apex.high));
+        let plan = if shortfall > 0.0 {
+            GapPlan { half_gap: clamped.half_gap + shortfall + APEX_SLACK, ..clamped }
+        } else {
+            clamped
+        };
+        self.accept(physics, plan, hover, Some(reach))
+    }
+
+    // 记录这对管道可用的弧顶高度，作为下一对管道的起点
+    fn accept(&mut self, physics: &CoursePhysics, plan: GapPlan, hover: bool, reach: Option<Corridor>) -> GapPlan {
+        let apex = physics.apex_range(&plan, hover);
+        let reachable = apex.zip(reach).and_then(|(apex, reach)| apex.intersect(reach));
+        self.previous = reachable.or(apex).map(|apex| (plan, apex));
+        plan
+    }
+}
+
+// 运动后的通道中心必须留在活动范围内，障碍物才能始终覆盖到屏幕边缘
+fn stays_in_range(plan: &GapPlan, gap_center_range: f32) -> bool {
+    match plan.pattern {
+        PipeMotionPattern::Oscillate { amplitude, .. } => plan.gap_y.abs() + amplitude <= gap_center_range,
+        _ => plan.gap_y.abs() <= gap_center_range,
+    }
+}
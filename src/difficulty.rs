@@ -0,0 +1,194 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// 难度描述文件所在的目录（相对于资源目录）和扩展名
+pub const DIFFICULTY_DIR: &str = "difficulty";
+pub const DIFFICULTY_EXTENSION: &str = "difficulty.json";
+
+// 难度曲线的自变量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CurveDriver {
+    Score, // 按当前分数
+    Time,  // 按本局已进行的秒数
+}
+
+// 分段线性曲线：按自变量排序的 [x, y] 点，超出两端时取端点的值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Curve(pub Vec<[f32; 2]>);
+
+impl Curve {
+    pub fn sample(&self, x: f32) -> f32 {
+        let points = &self.0;
+        let Some(first) = points.first() else {
+            return 0.0;
+        };
+        if x <= first[0] {
+            return first[1];
+        }
+        for pair in points.windows(2) {
+            let ([x0, y0], [x1, y1]) = (pair[0], pair[1]);
+            if x <= x1 {
+                let t = if x1 > x0 { (x - x0) / (x1 - x0) } else { 1.0 };
+                return y0 + (y1 - y0) * t;
+            }
+        }
+        points[points.len() - 1][1]
+    }
+}
+
+// 难度配置：描述各项参数如何随分数或时间变化
+// 每个预设一个 assets/difficulty/*.difficulty.json，修改后重新启动即可生效，不需要重新编译
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyCurve {
+    pub name: String,
+    pub driver: CurveDriver,
+    pub gap: Curve,               // 通道高度
+    pub pipe_speed: Curve,        // 管道滚动速度
+    pub spawn_interval: Curve,    // 管道生成间隔（秒）
+    pub gap_center_range: Curve,  // 通道中心的活动范围（±）
+    pub motion_difficulty: Curve, // 允许出现的最高运动难度，见 PipeMotionKind::difficulty_weight
+}
+
+impl DifficultyCurve {
+    // 根据分数和本局时间计算曲线自变量
+    pub fn progress(&self, score: u32, elapsed_secs: f32) -> f32 {
+        match self.driver {
+            CurveDriver::Score => score as f32,
+            CurveDriver::Time => elapsed_secs,
+        }
+    }
+}
+
+// 参数固定的经典难度：难度描述加载完成之前，以及描述缺少或格式错误时使用
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            name: "经典".to_string(),
+            driver: CurveDriver::Score,
+            gap: Curve(vec![[0.0, 150.0]]),
+            pipe_speed: Curve(vec![[0.0, 200.0]]),
+            spawn_interval: Curve(vec![[0.0, 2.0]]),
+            gap_center_range: Curve(vec![[0.0, 100.0]]),
+            motion_difficulty: Curve(vec![[0.0, 0.0]]),
+        }
+    }
+}
+
+// 难度描述文件的加载器
+#[derive(Default)]
+pub struct DifficultyCurveLoader;
+
+impl AssetLoader for DifficultyCurveLoader {
+    type Asset = DifficultyCurve;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[DIFFICULTY_EXTENSION]
+    }
+}
+
+// 难度预设，对应 assets/difficulty 下的描述文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum DifficultyPreset {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl DifficultyPreset {
+    pub const ALL: [DifficultyPreset; 4] = [
+        DifficultyPreset::Easy,
+        DifficultyPreset::Normal,
+        DifficultyPreset::Hard,
+        DifficultyPreset::Insane,
+    ];
+
+    // 描述文件的路径（相对于资源目录）
+    pub fn path(&self) -> String {
+        let name = match self {
+            DifficultyPreset::Easy => "easy",
+            DifficultyPreset::Normal => "normal",
+            DifficultyPreset::Hard => "hard",
+            DifficultyPreset::Insane => "insane",
+        };
+        format!("{}/{}.{}", DIFFICULTY_DIR, name, DIFFICULTY_EXTENSION)
+    }
+
+    // 界面文字的键，见 assets/locales
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            DifficultyPreset::Easy => "difficulty.easy",
+            DifficultyPreset::Normal => "difficulty.normal",
+            DifficultyPreset::Hard => "difficulty.hard",
+            DifficultyPreset::Insane => "difficulty.insane",
+        }
+    }
+
+    pub fn previous(&self) -> DifficultyPreset {
+        match self {
+            DifficultyPreset::Easy => DifficultyPreset::Insane,
+            DifficultyPreset::Normal => DifficultyPreset::Easy,
+            DifficultyPreset::Hard => DifficultyPreset::Normal,
+            DifficultyPreset::Insane => DifficultyPreset::Hard,
+        }
+    }
+
+    pub fn next(&self) -> DifficultyPreset {
+        match self {
+            DifficultyPreset::Easy => DifficultyPreset::Normal,
+            DifficultyPreset::Normal => DifficultyPreset::Hard,
+            DifficultyPreset::Hard => DifficultyPreset::Insane,
+            DifficultyPreset::Insane => DifficultyPreset::Easy,
+        }
+    }
+}
+
+// 各难度预设的曲线
+// 客户端在难度描述加载完成后插入；无头运行时可以直接读取（见 DifficultyCurves::load_from_dir）
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DifficultyCurves {
+    curves: HashMap<DifficultyPreset, DifficultyCurve>,
+}
+
+impl DifficultyCurves {
+    pub fn new(curves: HashMap<DifficultyPreset, DifficultyCurve>) -> Self {
+        Self { curves }
+    }
+
+    // 直接从资源目录读取所有预设的描述，供没有 AssetServer 的无头模拟和资源检查使用
+    pub fn load_from_dir(asset_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut curves = HashMap::new();
+        for preset in DifficultyPreset::ALL {
+            let path = asset_dir.join(preset.path());
+            let curve = serde_json::from_str(&fs::read_to_string(&path)?)
+                .map_err(|e| format!("难度描述格式错误 {}: {}", path.display(), e))?;
+            curves.insert(preset, curve);
+        }
+        Ok(Self::new(curves))
+    }
+
+    // 预设的曲线，描述缺少时使用经典难度
+    pub fn get(&self, preset: DifficultyPreset) -> DifficultyCurve {
+        self.curves.get(&preset).cloned().unwrap_or_default()
+    }
+}
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+// 拍打翅膀事件 - 由键盘/鼠标输入、机器人或测试发出，模拟核心统一处理
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct FlapEvent;
+
+// 通过管道事件 - 小鸟越过一对管道的计分门时发出，每对管道只发出一次
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PipePassed {
+    pub pair: Entity,
+    pub score: u32, // 计分后的总分
+}
@@ -0,0 +1,29 @@
+// Flappy Bird 游戏库
+//
+// SimulationPlugin 只包含游戏规则，可以在 MinimalPlugins 上无头运行；
+// ClientPlugin 在其之上叠加窗口、渲染、菜单和界面。
+
+// Bevy 系统的参数和查询类型天然较多、较长
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
+
+pub mod asset_check;
+pub mod audio;
+pub mod character;
+pub mod collision;
+pub mod states;
+pub mod components;
+pub mod course;
+pub mod difficulty;
+pub mod events;
+pub mod locale;
+pub mod parallax;
+pub mod replay;
+pub mod resources;
+pub mod settings;
+pub mod systems;
+pub mod theme;
+pub mod simulation;
+pub mod client;
+
+pub use simulation::{SimulationPlugin, SimulationSet, DEFAULT_TICK_RATE};
+pub use client::ClientPlugin;
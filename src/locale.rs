@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    ZhCn,
+    En,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::ZhCn, Language::En];
+
+    // 语言代码，与角色描述文件中 localized_names 的键一致
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::ZhCn => "zh-CN",
+            Language::En => "en",
+        }
+    }
+
+    pub fn next(&self) -> Language {
+        match self {
+            Language::ZhCn => Language::En,
+            Language::En => Language::ZhCn,
+        }
+    }
+
+    // 内置字符串表，对应 assets/locales 下的文件
+    fn strings(&self) -> HashMap<String, String> {
+        let source = match self {
+            Language::ZhCn => include_str!("../assets/locales/zh-CN.json"),
+            Language::En => include_str!("../assets/locales/en.json"),
+        };
+        serde_json::from_str(source).expect("内置字符串表格式错误")
+    }
+}
+
+// 当前语言的界面文字，切换语言时整体替换
+#[derive(Resource, Debug, Clone)]
+pub struct Locale {
+    language: Language,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>, // 缺少翻译时使用默认语言
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            strings: language.strings(),
+            fallback: Language::default().strings(),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.language.code()
+    }
+
+    // 查找文字，两种语言都没有时直接显示键名，便于发现遗漏
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    // 查找文字并替换其中的 {名称} 占位符
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    // 界面上可能显示的所有文字，用于检查字体能否显示这种语言
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.fallback.keys().chain(self.strings.keys()).map(|key| self.get(key))
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
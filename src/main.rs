@@ -1,76 +1,116 @@
-mod audio;
-mod states;
-mod components;
-mod resources;
-mod systems;
-
-use audio::*;
-use states::GameState;
-use components::*;
-use resources::*;
-use systems::*;
 use bevy::prelude::*;
+use bevy::window::WindowResolution;
+use flappy_bird::asset_check::{asset_dir, check_assets, FontChain};
+use flappy_bird::audio::AudioPlugin;
+use flappy_bird::locale::{Language, Locale};
+use flappy_bird::replay::*;
+use flappy_bird::resources::*;
+use flappy_bird::{ClientPlugin, SimulationPlugin};
+use std::path::Path;
 
 fn main() {
+    // --check-assets 只检查资源，不启动游戏
+    if std::env::args().any(|arg| arg == "--check-assets") {
+        std::process::exit(run_asset_check());
+    }
+    
     // 初始化数据持久化管理器
     let save_manager = SaveManager::new();
     let save_data = save_manager.load_data();
+    let settings = save_manager.load_settings();
+    let mut resolution = WindowResolution::new(800.0, 600.0);
+    resolution.set_scale_factor_override(settings.window_size.scale_factor_override());
+    
+    // 难度、障碍主题和顶部边界在每局开始时按设置写入
+    let config = GameConfig {
+        seed: parse_seed_arg(),
+        ..default()
+    };
     
-    App::new()
+    let mut app = App::new();
+    app
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Flappy Bird".into(),
-                resolution: (800.0, 600.0).into(),
+                resolution,
                 resizable: false,
                 ..default()
             }),
             ..default()
         }).set(ImagePlugin::default_nearest()))
-        .init_state::<GameState>()
-        .insert_resource(ClearColor(Color::srgb(0.34, 0.75, 0.79)))
-        .insert_resource(GameData {
-            score: 0,
-            high_score: save_data.high_score,
-            selected_character: save_data.selected_character,
-            save_data: save_data.clone(),
-        })
+        .add_plugins((SimulationPlugin::default(), ClientPlugin, AudioPlugin))
+        .insert_resource(GameData::from_save_data(save_data))
+        .insert_resource(Locale::new(settings.language))
         .insert_resource(save_manager)
-        .insert_resource(GameConfig {
-            jump_force: 400.0,
-            pipe_speed: 200.0,
-            pipe_gap: 150.0,
-            pipe_spawn_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-        })
-        .add_systems(Startup, (setup_camera, load_assets, set_window_icon))
-        .add_systems(
-            Update,
-            (
-                setup_menu_when_ready.run_if(in_state(GameState::Menu)),
-                menu_system.run_if(in_state(GameState::Menu)),
-                character_selection_system.run_if(in_state(GameState::Menu)),
-                (
-                    bird_input_system,
-                    bird_physics_system,
-                    wing_animation_system,
-                    pipe_spawn_system,
-                    scrolling_system,
-                    collision_system,
-                    score_system,
-                    number_score_display,
-                )
-                    .run_if(in_state(GameState::Playing)),
-                game_over_system.run_if(in_state(GameState::GameOver)),
-                leaderboard_system.run_if(in_state(GameState::Leaderboard)),
-                restart_system,
-            ),
-        )
-        .add_systems(OnExit(GameState::Menu), cleanup_menu)
-        .add_systems(OnEnter(GameState::Playing), setup_game)
-        .add_systems(OnExit(GameState::Playing), cleanup_game)
-        .add_systems(OnEnter(GameState::GameOver), (setup_game_over, on_game_over, save_game_data))
-        .add_systems(OnExit(GameState::GameOver), cleanup_game_over)
-        .add_systems(OnEnter(GameState::Leaderboard), setup_leaderboard)
-        .add_systems(OnExit(GameState::Leaderboard), cleanup_leaderboard)
-        .add_plugins(AudioPlugin)
-        .run();
-}
\ No newline at end of file
+        .insert_resource(config)
+        .insert_resource(settings);
+
+    // --replay <文件> 启动后直接播放回放，资源加载完成后由 start_pending_replay 进入回放状态
+    if let Some(path) = arg_value("--replay") {
+        match Replay::load(Path::new(&path)) {
+            Ok(replay) => {
+                app.insert_resource(PendingReplay(replay));
+            }
+            Err(e) => eprintln!("加载回放失败: {}", e),
+        }
+    }
+
+    app.run();
+}
+
+// 检查所有引用的资源和字体链，逐个列出问题；有问题时返回非零退出码
+fn run_asset_check() -> i32 {
+    let dir = asset_dir();
+    let report = check_assets(&dir);
+    let fonts = FontChain::load(&dir);
+    
+    println!("资源目录: {}", dir.display());
+    println!("已检查 {} 个文件", report.checked);
+    for font in &fonts.fonts {
+        let coverage: Vec<String> = Language::ALL
+            .into_iter()
+            .map(|language| match font.missing.get(&language) {
+                Some(missing) if !missing.is_empty() => {
+                    format!("{} ✗ 缺少 {}", language.code(), missing.iter().collect::<String>())
+                }
+                _ => format!("{} ✓", language.code()),
+            })
+            .collect();
+        println!("字体: {}（{}）", font.path.display(), coverage.join("，"));
+    }
+    
+    // 系统字体不属于游戏资源，只提示不计入问题
+    for warning in &fonts.warnings {
+        println!("警告 {}", warning);
+    }
+    
+    let problems: Vec<_> = report.problems.iter().chain(&fonts.problems).collect();
+    if problems.is_empty() {
+        println!("所有资源正常");
+        return 0;
+    }
+    for problem in &problems {
+        eprintln!("资源错误 {}", problem);
+    }
+    eprintln!("共 {} 个问题", problems.len());
+    1
+}
+
+// 读取命令行参数 `name` 之后的值
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
+}
+
+// 解析命令行参数 --seed <u64>，用于复现指定的管道布局
+fn parse_seed_arg() -> Option<u64> {
+    let seed = arg_value("--seed")?;
+    match seed.parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => {
+            eprintln!("无效的种子: {}", seed);
+            None
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Deserialize;
+
+// 视差背景层：背景由 assets/backgrounds/parallax.json 描述，新增一层只需要修改描述文件
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParallaxLayer {
+    pub name: String,
+    pub textures: Vec<String>, // 贴图池，循环模式下每次回到右侧时随机换一张
+    pub depth: f32,            // 绘制顺序（z），越小越靠后
+    pub speed_factor: f32,     // 滚动速度相对管道速度的比例，越远越慢
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default = "default_tinted")]
+    pub tinted: bool, // 是否随天空主题变色，星星等自身发光的层不变色
+    pub y: [f32; 2], // 纵向位置范围，循环模式下每次回到右侧时在范围内随机
+    pub mode: ParallaxMode,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_tinted() -> bool {
+    true
+}
+
+// 层内元素移出屏幕左侧后的处理方式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParallaxMode {
+    // 无缝平铺：首尾相接铺满屏幕，width 为缩放后单张贴图的宽度
+    Tile { width: f32 },
+    // 随机循环：count 个元素从 spacing 间隔开始，移出左侧后隔一段随机距离回到右侧
+    Recycle { count: usize, spacing: f32, gap: [f32; 2] },
+}
+
+impl ParallaxMode {
+    // 层内的元素数量，平铺时刚好覆盖屏幕宽度再多一张
+    pub fn count(&self, screen_width: f32) -> usize {
+        match self {
+            ParallaxMode::Tile { width } => (screen_width / width.max(1.0)).ceil() as usize + 1,
+            ParallaxMode::Recycle { count, .. } => *count,
+        }
+    }
+}
+
+// 内置的视差背景层，从远到近排列
+pub fn builtin_layers() -> Vec<ParallaxLayer> {
+    serde_json::from_str(include_str!("../assets/backgrounds/parallax.json"))
+        .expect("内置视差背景描述格式错误")
+}
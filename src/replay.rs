@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use crate::character::{CharacterManifest, CharacterProfile, CharacterRegistry};
+use crate::components::{BirdCharacter, CeilingMode, PipeTheme};
+use crate::difficulty::DifficultyCurve;
+use crate::resources::GameConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+// 回放文件格式版本
+// 2: 新增节日障碍物，管道类型的随机序列与旧版本不同
+// 3: 新增障碍物运动模式
+// 4: 管道参数改为记录完整的难度曲线
+// 5: 通道位置按可达性约束修正，同一种子生成的关卡与旧版本不同
+// 6: 重力、拍打力度和最大下落速度改由角色决定，不再记录 jump_force
+// 7: 新增随管道滚动的地面碰撞体，并记录顶部边界的行为（阻挡或撞击），旧版本的回放无法复现
+// 8: 可达范围超出活动范围时加宽通道，传统管道也做像素级碰撞检测
+// 9: 记录录制时角色的属性、碰撞框和缩放，角色描述修改后不再播放
+pub const REPLAY_VERSION: u32 = 9;
+
+// 自动保存的回放文件名
+pub const LAST_REPLAY: &str = "last";
+pub const BEST_REPLAY: &str = "best";
+
+// 回放中记录的游戏参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayConfig {
+    pub pipe_theme: PipeTheme,
+    pub ceiling: CeilingMode,
+    pub difficulty: DifficultyCurve,
+}
+
+impl ReplayConfig {
+    pub fn from_config(config: &GameConfig) -> Self {
+        Self {
+            pipe_theme: config.pipe_theme,
+            ceiling: config.ceiling,
+            difficulty: config.difficulty.clone(),
+        }
+    }
+
+    // 把记录的参数写回游戏配置
+    pub fn apply_to(&self, config: &mut GameConfig) {
+        config.pipe_theme = self.pipe_theme;
+        config.ceiling = self.ceiling;
+        config.difficulty = self.difficulty.clone();
+        config.apply_difficulty(0.0);
+    }
+}
+
+// 一局游戏的完整记录：种子、参数、角色以及每次拍打发生的固定步序号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub timestep: Duration, // 固定步长，必须与录制时完全一致才能复现
+    pub config: ReplayConfig,
+    pub character: BirdCharacter,
+    pub character_profile: CharacterProfile, // 录制时的角色参数，角色描述修改后回放无法复现
+    pub flaps: Vec<u64>,
+    pub score: u32, // 录制时的最终分数，回放后用于校验
+    #[serde(default)]
+    pub ticks: u64, // 撞击前运行的固定步数
+}
+
+impl Replay {
+    pub fn new(seed: u64, timestep: Duration, config: ReplayConfig, character: &CharacterManifest) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            timestep,
+            config,
+            character: character.id.clone(),
+            character_profile: character.profile(),
+            flaps: Vec::new(),
+            score: 0,
+            ticks: 0,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let replay: Replay = serde_json::from_str(&content)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!("不支持的回放版本: {}", replay.version).into());
+        }
+        Ok(replay)
+    }
+
+    // 检查录制时的角色能否在当前的角色描述下复现，不能时返回原因
+    pub fn check_character(&self, characters: &CharacterRegistry) -> Result<(), String> {
+        let Some(character) = characters.get(&self.character) else {
+            return Err(format!("角色 {} 不存在", self.character.0));
+        };
+        if character.profile() != self.character_profile {
+            return Err(format!("角色 {} 的描述在录制后被修改", self.character.0));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // 使用紧凑格式，拍打序列可能很长
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+// 推进拍打游标，返回固定步 `tick` 上是否有拍打
+pub fn take_flap(flaps: &[u64], next_flap: &mut usize, tick: u64) -> bool {
+    let mut flapped = false;
+    while let Some(&flap_tick) = flaps.get(*next_flap) {
+        if flap_tick > tick {
+            break;
+        }
+        flapped |= flap_tick == tick;
+        *next_flap += 1;
+    }
+    flapped
+}
+
+// 当前这局的录制
+#[derive(Resource)]
+pub struct ReplayRecorder(pub Replay);
+
+// 启动参数 --replay 指定的回放，贴图、角色和碰撞遮罩都就绪后才开始播放
+#[derive(Resource)]
+pub struct PendingReplay(pub Replay);
+
+// 本局幽灵小鸟重放的最佳回放，本局使用与它相同的种子和角色，幽灵才会飞过同一条关卡
+#[derive(Resource)]
+pub struct GhostRun(pub Replay);
+
+// 正在播放的回放
+// 回放结束后继续保留到下一局实时游戏开始，游戏结束界面据此区分回放和实时对局
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub next_flap: usize,
+    // 回放开始前的配置和步长，回放结束时恢复
+    pub previous_config: Option<ReplayConfig>,
+    pub previous_timestep: Option<Duration>,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next_flap: 0,
+            previous_config: None,
+            previous_timestep: None,
+        }
+    }
+}
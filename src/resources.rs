@@ -1,8 +1,17 @@
+use bevy::asset::LoadedFolder;
 use bevy::prelude::*;
-use crate::components::{BirdCharacter, PipeType};
+use crate::components::{BirdCharacter, CeilingMode, PipeTheme, PipeType};
+use crate::difficulty::{DifficultyCurve, DifficultyCurves, DifficultyPreset};
+use crate::locale::Language;
+use crate::parallax::ParallaxLayer;
+use crate::settings::Settings;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 // 排行榜条目
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,28 +19,64 @@ pub struct LeaderboardEntry {
     pub score: u32,
     pub character: BirdCharacter,
     pub timestamp: u64, // Unix时间戳
-    pub player_name: String,
+    #[serde(default)]
+    pub seed: Option<u64>, // 本局随机种子，旧存档没有该字段
 }
 
-// 持久化数据结构
+// 每日挑战记录 - 每个本地日期一条
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyEntry {
+    pub date: String, // 本地日期，格式 YYYY-MM-DD
+    pub score: u32,
+    pub character: BirdCharacter,
+    pub seed: u64,
+}
+
+// 每个角色的排行榜保留的条目数
+const LEADERBOARD_SIZE: usize = 10;
+
+// 最多保留的每日挑战天数
+const MAX_DAILY_ENTRIES: usize = 30;
+
+// 当前本地日期，格式 YYYY-MM-DD
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+// 每日挑战的种子：日期的数字形式，例如 2026-10-18 -> 20261018
+// 同一天所有玩家得到相同的管道布局
+pub fn daily_seed(date: &str) -> u64 {
+    date.bytes()
+        .filter(u8::is_ascii_digit)
+        .fold(0, |seed, digit| seed * 10 + u64::from(digit - b'0'))
+}
+
+// 每日挑战统一使用的角色
+// 通道按角色的物理参数和碰撞框修正，角色不同时同一个种子生成的布局也不同
+pub fn daily_character() -> BirdCharacter {
+    BirdCharacter::default()
+}
+
+// 持久化数据结构，偏好设置单独保存（见 Settings）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SaveData {
     pub high_score: u32,
     pub selected_character: BirdCharacter,
     pub leaderboard: Vec<LeaderboardEntry>,
     pub total_games: u32,
     pub total_score: u32,
+    #[serde(default)]
+    pub daily: Vec<DailyEntry>, // 每日挑战成绩，按日期倒序，不计入全局排行榜
 }
 
-impl Default for SaveData {
-    fn default() -> Self {
-        Self {
-            high_score: 0,
-            selected_character: BirdCharacter::YellowBird,
-            leaderboard: Vec::new(),
-            total_games: 0,
-            total_score: 0,
-        }
+impl SaveData {
+    // 某个角色的排行榜，按分数从高到低
+    pub fn leaderboard_for<'a>(&'a self, character: &'a BirdCharacter) -> impl Iterator<Item = &'a LeaderboardEntry> {
+        self.leaderboard.iter().filter(move |entry| entry.character == *character)
+    }
+    
+    pub fn daily_entry(&self, date: &str) -> Option<&DailyEntry> {
+        self.daily.iter().find(|entry| entry.date == date)
     }
 }
 
@@ -44,12 +89,43 @@ pub struct GameData {
     pub save_data: SaveData,
 }
 
+impl GameData {
+    pub fn from_save_data(save_data: SaveData) -> Self {
+        Self {
+            score: 0,
+            high_score: save_data.high_score,
+            selected_character: save_data.selected_character.clone(),
+            save_data,
+        }
+    }
+}
+
+impl Default for GameData {
+    fn default() -> Self {
+        Self::from_save_data(SaveData::default())
+    }
+}
+
+// 当前这局的类型
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Default)]
+pub enum RunMode {
+    #[default]
+    Normal,
+    Daily { date: String }, // 每日挑战，每天只有一次计分机会
+}
+
 // 数据持久化管理器
 #[derive(Resource)]
 pub struct SaveManager {
     pub save_path: PathBuf,
 }
 
+impl Default for SaveManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SaveManager {
     pub fn new() -> Self {
         let mut save_path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -79,7 +155,47 @@ impl SaveManager {
         Ok(())
     }
     
-    pub fn add_score_to_leaderboard(&self, mut save_data: SaveData, score: u32, character: BirdCharacter) -> SaveData {
+    // 设置文件路径，与存档放在同一目录下
+    pub fn settings_path(&self) -> PathBuf {
+        self.save_path.with_file_name("settings.json")
+    }
+    
+    // 读取设置；还没有设置文件时从旧版本的存档中迁移偏好字段并立即保存
+    pub fn load_settings(&self) -> Settings {
+        if let Ok(content) = fs::read_to_string(self.settings_path()) {
+            return serde_json::from_str(&content).unwrap_or_default();
+        }
+        
+        let settings: Settings = fs::read_to_string(&self.save_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if let Err(e) = self.save_settings(&settings) {
+            eprintln!("保存设置失败: {}", e);
+        }
+        settings
+    }
+    
+    pub fn save_settings(&self, settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(settings)?;
+        fs::write(self.settings_path(), json)?;
+        Ok(())
+    }
+    
+    // 回放文件路径，与存档放在同一目录下
+    pub fn replay_path(&self, name: &str) -> PathBuf {
+        let mut path = self.save_path.with_file_name("replays");
+        path.push(format!("{}.json", name));
+        path
+    }
+    
+    pub fn add_score_to_leaderboard(
+        &self,
+        mut save_data: SaveData,
+        score: u32,
+        character: BirdCharacter,
+        seed: u64,
+    ) -> SaveData {
         let entry = LeaderboardEntry {
             score,
             character,
@@ -87,14 +203,19 @@ impl SaveManager {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
-            player_name: format!("玩家{}", character.get_name()),
+            seed: Some(seed),
         };
         
         save_data.leaderboard.push(entry);
         
-        // 按分数排序，保留前10名
-        save_data.leaderboard.sort_by(|a, b| b.score.cmp(&a.score));
-        save_data.leaderboard.truncate(10);
+        // 按分数排序，每个角色的手感不同，各自保留前10名
+        save_data.leaderboard.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        let mut counts = HashMap::new();
+        save_data.leaderboard.retain(|entry| {
+            let count = counts.entry(entry.character.clone()).or_insert(0);
+            *count += 1;
+            *count <= LEADERBOARD_SIZE
+        });
         
         // 更新统计数据
         save_data.total_games += 1;
@@ -106,54 +227,250 @@ impl SaveManager {
         
         save_data
     }
+    
+    // 记录每日挑战成绩，同一天只保留一条，不进入全局排行榜
+    pub fn record_daily_result(&self, mut save_data: SaveData, date: &str, score: u32, character: BirdCharacter) -> SaveData {
+        match save_data.daily.iter_mut().find(|entry| entry.date == date) {
+            Some(entry) => {
+                entry.score = score;
+                entry.character = character;
+            }
+            None => save_data.daily.push(DailyEntry {
+                date: date.to_string(),
+                score,
+                character,
+                seed: daily_seed(date),
+            }),
+        }
+        
+        // 按日期倒序，只保留最近的记录
+        save_data.daily.sort_by(|a, b| b.date.cmp(&a.date));
+        save_data.daily.truncate(MAX_DAILY_ENTRIES);
+        
+        save_data
+    }
 }
 
 #[derive(Resource)]
 pub struct GameConfig {
-    pub jump_force: f32,
+    pub seed: Option<u64>, // 固定随机种子（命令行 --seed），为空时每局随机
+    pub pipe_theme: PipeTheme,
+    pub ceiling: CeilingMode,
+    pub difficulty: DifficultyCurve, // 当前使用的难度曲线
+    // 以下参数由难度曲线随分数或时间更新
     pub pipe_speed: f32,
     pub pipe_gap: f32,
     pub pipe_spawn_timer: Timer,
+    pub gap_center_range: f32,
+    pub motion_difficulty: f32,
+}
+
+impl GameConfig {
+    pub fn with_difficulty(difficulty: DifficultyCurve) -> Self {
+        let mut config = Self {
+            seed: None,
+            pipe_theme: PipeTheme::default(),
+            ceiling: CeilingMode::default(),
+            difficulty,
+            pipe_speed: 0.0,
+            pipe_gap: 0.0,
+            pipe_spawn_timer: Timer::from_seconds(0.0, TimerMode::Repeating),
+            gap_center_range: 0.0,
+            motion_difficulty: 0.0,
+        };
+        config.apply_difficulty(0.0);
+        config
+    }
+    
+    // 每日挑战统一使用普通难度、默认障碍主题和顶部边界，不受玩家设置影响
+    pub fn use_daily_rules(&mut self, curves: &DifficultyCurves) {
+        self.difficulty = curves.get(DifficultyPreset::Normal);
+        self.pipe_theme = PipeTheme::default();
+        self.ceiling = CeilingMode::default();
+    }
+    
+    // 按难度曲线在 progress 处的取值更新参数
+    pub fn apply_difficulty(&mut self, progress: f32) {
+        let curve = &self.difficulty;
+        self.pipe_speed = curve.pipe_speed.sample(progress);
+        self.pipe_gap = curve.gap.sample(progress);
+        self.gap_center_range = curve.gap_center_range.sample(progress);
+        self.motion_difficulty = curve.motion_difficulty.sample(progress);
+        let interval = Duration::from_secs_f32(curve.spawn_interval.sample(progress).max(0.1));
+        if self.pipe_spawn_timer.duration() != interval {
+            self.pipe_spawn_timer.set_duration(interval);
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self::with_difficulty(DifficultyCurve::default())
+    }
+}
+
+// 本局的模拟进度
+#[derive(Resource, Default)]
+pub struct SimulationClock {
+    pub tick: u64,     // 本局已经运行的固定步数
+    pub crashed: bool, // 小鸟已经撞击，本局剩余的固定步不再模拟
+}
+
+// 顶部边界与窗口上边缘之间留出的距离
+pub const BOUNDARY_MARGIN: f32 = 20.0;
+
+// 窗口底部地面条的高度
+pub const GROUND_HEIGHT: f32 = 40.0;
+
+// 地面向左滚动该距离后回到原位，必须是地面贴图宽度的整数倍才能无缝衔接，
+// 并且大于渲染插值的瞬移距离
+pub const GROUND_SCROLL_WRAP: f32 = 240.0;
+
+// 可活动区域 - 客户端根据窗口尺寸更新
+// 小鸟碰到地面（地面顶部位于 floor）即撞击，碰到顶部时按 CeilingMode 撞击或被阻挡
+// 滚动的实体整个越过左边缘（left）后移除
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PlayfieldBounds {
+    pub ceiling: f32,
+    pub floor: f32,
+    pub left: f32,
+}
+
+impl PlayfieldBounds {
+    pub fn from_window_size(size: Vec2) -> Self {
+        Self {
+            ceiling: size.y / 2.0 - BOUNDARY_MARGIN,
+            floor: -size.y / 2.0 + GROUND_HEIGHT,
+            left: -size.x / 2.0,
+        }
+    }
+}
+
+impl Default for PlayfieldBounds {
+    fn default() -> Self {
+        Self::from_window_size(Vec2::new(800.0, 600.0))
+    }
+}
+
+// 暂停结束后的倒计时
+#[derive(Resource)]
+pub struct ResumeCountdown(pub Timer);
+
+// 窗口当前是否拥有焦点，由始终运行的系统根据焦点事件更新
+#[derive(Resource)]
+pub struct WindowFocus(pub bool);
+
+impl Default for WindowFocus {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+// 小鸟坠落到底后、显示结算前的停留时间
+#[derive(Resource)]
+pub struct DeathLinger(pub Timer);
+
+// 可复现的随机数资源 - 所有游戏内随机数都必须从这里抽取
+// 管道和背景使用两条由同一种子派生的独立序列，
+// 这样无头模拟（没有背景）和客户端会生成完全相同的管道
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    gameplay: StdRng,
+    scenery: StdRng,
+}
+
+impl GameRng {
+    // 背景序列的种子偏移，避免与管道序列相同
+    const SCENERY_STREAM: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            gameplay: StdRng::seed_from_u64(seed),
+            scenery: StdRng::seed_from_u64(seed ^ Self::SCENERY_STREAM),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // 用新种子重置两条序列
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    // 影响游戏结果的随机数（管道位置、类型）
+    pub fn gameplay(&mut self) -> &mut StdRng {
+        &mut self.gameplay
+    }
+
+    // 纯装饰的随机数（山脉、云朵循环）
+    pub fn scenery(&mut self) -> &mut StdRng {
+        &mut self.scenery
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+// GameAssets 引用的固定贴图（相对于资源目录）
+pub const GROUND_TEXTURE: &str = "ground.png";
+pub const SPACE_TO_START_TEXTURE: &str = "SpaceToStart.png";
+pub const WINDOW_ICON: &str = "icon.png";
+
+pub fn number_texture_path(digit: u32) -> String {
+    format!("numbers/{}.png", digit)
 }
 
 #[derive(Resource)]
 pub struct GameAssets {
-    pub bird_textures: Vec<Handle<Image>>,  // 存储所有小鸟纹理
-    pub bird_animation_frames: Vec<Vec<Handle<Image>>>,  // 存储所有小鸟的动画帧
-    pub pipe_texture: Handle<Image>,
+    pub character_folder: Handle<LoadedFolder>, // assets/characters 下的角色描述
+    pub difficulty_curves: HashMap<DifficultyPreset, Handle<DifficultyCurve>>, // 各难度预设的描述
+    pub bird_textures: HashMap<BirdCharacter, Handle<Image>>,  // 角色描述加载完成后填充
+    pub bird_sheets: HashMap<BirdCharacter, (Handle<Image>, Handle<TextureAtlasLayout>)>, // 有精灵表的角色
+    pub pipe_textures: Vec<Handle<Image>>, // 按 PipeType::all_types 的顺序存储
     pub ground_texture: Handle<Image>,
-    pub cloud_texture: Handle<Image>,
-    pub mountain_texture: Handle<Image>,
-    pub font: Handle<Font>,
+    pub parallax_layers: Vec<ParallaxLayer>, // 视差背景层，从远到近
+    pub parallax_textures: Vec<Vec<Handle<Image>>>, // 每层的贴图池，与 parallax_layers 一一对应
+    pub space_to_start_texture: Handle<Image>,
+    pub fonts: HashMap<Language, Handle<Font>>, // 各语言界面使用的字体，没有可用字体的语言不在其中
     pub number_textures: Vec<Handle<Image>>,
 }
 
 impl GameAssets {
-    pub fn get_bird_texture(&self, character: BirdCharacter) -> Handle<Image> {
-        let index = match character {
-            BirdCharacter::YellowBird => 0,
-            BirdCharacter::RedBird => 1,
-            BirdCharacter::BlueBird => 2,
-            BirdCharacter::WuSaQi => 3,
-            BirdCharacter::JiYi => 4,
-            BirdCharacter::XiaoBa => 5,
-        };
-        self.bird_textures[index].clone()
+    // 语言界面使用的字体，没有可用字体时使用 Bevy 内置字体
+    pub fn font(&self, language: Language) -> Handle<Font> {
+        self.fonts.get(&language).cloned().unwrap_or_default()
+    }
+
+    pub fn get_bird_texture(&self, character: &BirdCharacter) -> Handle<Image> {
+        self.bird_textures.get(character).cloned().unwrap_or_default()
     }
     
-    pub fn get_bird_animation_frames(&self, character: BirdCharacter) -> Vec<Handle<Image>> {
-        let index = match character {
-            BirdCharacter::YellowBird => 0,
-            BirdCharacter::RedBird => 1,
-            BirdCharacter::BlueBird => 2,
-            BirdCharacter::WuSaQi => 3,
-            BirdCharacter::JiYi => 4,
-            BirdCharacter::XiaoBa => 5,
-        };
-        self.bird_animation_frames[index].clone()
+    // 小鸟的精灵：有精灵表时使用精灵表的第一帧，否则使用静态贴图
+    pub fn get_bird_sprite(&self, character: &BirdCharacter) -> Sprite {
+        match self.bird_sheets.get(character) {
+            Some((image, layout)) => Sprite::from_atlas_image(
+                image.clone(),
+                TextureAtlas {
+                    layout: layout.clone(),
+                    index: 0,
+                },
+            ),
+            None => Sprite::from_image(self.get_bird_texture(character)),
+        }
     }
     
     pub fn get_pipe_texture(&self, pipe_type: PipeType) -> Handle<Image> {
-        self.pipe_texture.clone()
+        let index = PipeType::all_types()
+            .iter()
+            .position(|candidate| *candidate == pipe_type)
+            .unwrap_or(0);
+        self.pipe_textures[index].clone()
     }
 }
\ No newline at end of file
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use crate::components::{CeilingMode, PipeTheme};
+use crate::difficulty::{DifficultyCurves, DifficultyPreset};
+use crate::locale::Language;
+use crate::resources::GameConfig;
+use crate::theme::{SkyTheme, Weather};
+use serde::{Deserialize, Serialize};
+
+// 玩家偏好设置，由 SaveManager 保存在存档旁边的 settings.json
+// 缺少的字段使用默认值，旧版本写在存档里的偏好字段同名，可以直接读取
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub volume: u32, // 音量百分比，0-100
+    pub window_size: WindowSize,
+    pub language: Language,
+    pub flap_key: FlapKey,
+    pub difficulty: DifficultyPreset,
+    pub ghost_enabled: bool, // 是否显示个人最佳的幽灵小鸟
+    pub pipe_theme: PipeTheme,
+    pub ceiling: CeilingMode,
+    pub sky_theme: SkyTheme,
+    pub weather: Weather,
+}
+
+// 音量每次调整的幅度
+pub const VOLUME_STEP: u32 = 10;
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            window_size: WindowSize::default(),
+            language: Language::default(),
+            flap_key: FlapKey::default(),
+            difficulty: DifficultyPreset::default(),
+            ghost_enabled: true,
+            pipe_theme: PipeTheme::default(),
+            ceiling: CeilingMode::default(),
+            sky_theme: SkyTheme::default(),
+            weather: Weather::default(),
+        }
+    }
+}
+
+impl Settings {
+    // 把影响关卡的设置写入游戏配置，每局普通对局开始前调用
+    pub fn apply_to(&self, config: &mut GameConfig, curves: &DifficultyCurves) {
+        config.difficulty = curves.get(self.difficulty);
+        config.pipe_theme = self.pipe_theme;
+        config.ceiling = self.ceiling;
+    }
+
+    // 线性音量，1.0 为原始音量
+    pub fn linear_volume(&self) -> f32 {
+        self.volume.min(100) as f32 / 100.0
+    }
+}
+
+// 窗口大小：游戏画面始终是 800×600 个逻辑像素，按倍数放大显示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WindowSize {
+    #[default]
+    Auto, // 跟随系统的缩放比例
+    Small,
+    Medium,
+    Large,
+}
+
+impl WindowSize {
+    // 界面文字的键，见 assets/locales
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            WindowSize::Auto => "window_size.auto",
+            WindowSize::Small => "window_size.small",
+            WindowSize::Medium => "window_size.medium",
+            WindowSize::Large => "window_size.large",
+        }
+    }
+
+    // 覆盖系统缩放比例的倍数，为空时使用系统设置
+    pub fn scale_factor_override(&self) -> Option<f32> {
+        match self {
+            WindowSize::Auto => None,
+            WindowSize::Small => Some(1.0),
+            WindowSize::Medium => Some(1.5),
+            WindowSize::Large => Some(2.0),
+        }
+    }
+
+    pub fn previous(&self) -> WindowSize {
+        match self {
+            WindowSize::Auto => WindowSize::Large,
+            WindowSize::Small => WindowSize::Auto,
+            WindowSize::Medium => WindowSize::Small,
+            WindowSize::Large => WindowSize::Medium,
+        }
+    }
+
+    pub fn next(&self) -> WindowSize {
+        match self {
+            WindowSize::Auto => WindowSize::Small,
+            WindowSize::Small => WindowSize::Medium,
+            WindowSize::Medium => WindowSize::Large,
+            WindowSize::Large => WindowSize::Auto,
+        }
+    }
+}
+
+// 拍打使用的按键，鼠标左键始终可用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FlapKey {
+    #[default]
+    Space,
+    ArrowUp,
+    Enter,
+}
+
+impl FlapKey {
+    pub fn key_code(&self) -> KeyCode {
+        match self {
+            FlapKey::Space => KeyCode::Space,
+            FlapKey::ArrowUp => KeyCode::ArrowUp,
+            FlapKey::Enter => KeyCode::Enter,
+        }
+    }
+
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            FlapKey::Space => "flap_key.space",
+            FlapKey::ArrowUp => "flap_key.arrow_up",
+            FlapKey::Enter => "flap_key.enter",
+        }
+    }
+
+    pub fn next(&self) -> FlapKey {
+        match self {
+            FlapKey::Space => FlapKey::ArrowUp,
+            FlapKey::ArrowUp => FlapKey::Enter,
+            FlapKey::Enter => FlapKey::Space,
+        }
+    }
+}
+
+// 设置界面中的各行，按显示顺序排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsItem {
+    Volume,
+    WindowSize,
+    Language,
+    FlapKey,
+    Difficulty,
+    Ghost,
+    PipeTheme,
+    Ceiling,
+    SkyTheme,
+    Weather,
+    Back, // 返回菜单，方便只用鼠标操作
+}
+
+impl SettingsItem {
+    pub const ALL: [SettingsItem; 11] = [
+        SettingsItem::Volume,
+        SettingsItem::WindowSize,
+        SettingsItem::Language,
+        SettingsItem::FlapKey,
+        SettingsItem::Difficulty,
+        SettingsItem::Ghost,
+        SettingsItem::PipeTheme,
+        SettingsItem::Ceiling,
+        SettingsItem::SkyTheme,
+        SettingsItem::Weather,
+        SettingsItem::Back,
+    ];
+
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            SettingsItem::Volume => "settings.volume",
+            SettingsItem::WindowSize => "settings.window_size",
+            SettingsItem::Language => "settings.language",
+            SettingsItem::FlapKey => "settings.flap_key",
+            SettingsItem::Difficulty => "settings.difficulty",
+            SettingsItem::Ghost => "settings.ghost",
+            SettingsItem::PipeTheme => "settings.pipe_theme",
+            SettingsItem::Ceiling => "settings.ceiling",
+            SettingsItem::SkyTheme => "settings.sky_theme",
+            SettingsItem::Weather => "settings.weather",
+            SettingsItem::Back => "settings.back",
+        }
+    }
+
+    // 调整该项，forward 为 false 时反向切换；返回设置是否改变
+    pub fn adjust(&self, settings: &mut Settings, forward: bool) -> bool {
+        let before = settings.clone();
+        match self {
+            SettingsItem::Volume => {
+                settings.volume = if forward {
+                    (settings.volume + VOLUME_STEP).min(100)
+                } else {
+                    settings.volume.saturating_sub(VOLUME_STEP)
+                };
+            }
+            SettingsItem::WindowSize => {
+                settings.window_size = if forward { settings.window_size.next() } else { settings.window_size.previous() };
+            }
+            SettingsItem::Difficulty => {
+                settings.difficulty = if forward { settings.difficulty.next() } else { settings.difficulty.previous() };
+            }
+            // 其余设置只向前循环
+            SettingsItem::Language => settings.language = settings.language.next(),
+            SettingsItem::FlapKey => settings.flap_key = settings.flap_key.next(),
+            SettingsItem::Ghost => settings.ghost_enabled = !settings.ghost_enabled,
+            SettingsItem::PipeTheme => settings.pipe_theme = settings.pipe_theme.next(),
+            SettingsItem::Ceiling => settings.ceiling = settings.ceiling.next(),
+            SettingsItem::SkyTheme => settings.sky_theme = settings.sky_theme.next(),
+            SettingsItem::Weather => settings.weather = settings.weather.next(),
+            SettingsItem::Back => {}
+        }
+        *settings != before
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use crate::audio::AudioEvent;
+use crate::character::CharacterRegistry;
+use crate::collision::CollisionMasks;
+use crate::course::CoursePlanner;
+use crate::events::{FlapEvent, PipePassed};
+use crate::resources::*;
+use crate::states::{GameState, PlayPhase};
+use crate::systems::*;
+
+// 模拟系统集合 - 在 FixedUpdate 中按固定步长运行
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationSet;
+
+// 默认模拟频率（每秒固定步数）
+pub const DEFAULT_TICK_RATE: f64 = 60.0;
+
+// 无头模拟插件：物理、管道生成、滚动、碰撞和计分
+// 不依赖窗口、渲染、音频或 AssetServer，可以直接运行在 MinimalPlugins 上
+// 无头运行时需要插入 CharacterRegistry 和 CollisionMasks（见两者的 load_from_dir）
+// 所有模拟都以固定步长运行，结果与显示器刷新率无关
+pub struct SimulationPlugin {
+    pub tick_rate: f64,
+}
+
+impl Default for SimulationPlugin {
+    fn default() -> Self {
+        Self {
+            tick_rate: DEFAULT_TICK_RATE,
+        }
+    }
+}
+
+// 正在进行一局、没有暂停且小鸟还没有撞击时才推进模拟
+pub fn simulation_running(
+    state: Res<State<GameState>>,
+    phase: Option<Res<State<PlayPhase>>>,
+    clock: Res<SimulationClock>,
+    characters: Option<Res<CharacterRegistry>>,
+    masks: Option<Res<CollisionMasks>>,
+) -> bool {
+    // 角色属性来自角色描述，碰撞几何来自贴图，两者就绪前不推进模拟，保证结果与加载速度无关
+    characters.is_some()
+        && masks.is_some()
+        && state.get().is_running()
+        && phase.map_or(true, |phase| *phase.get() == PlayPhase::Running)
+        && !clock.crashed
+}
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        // MinimalPlugins 不包含状态插件
+        if !app.is_plugin_added::<StatesPlugin>() {
+            app.add_plugins(StatesPlugin);
+        }
+
+        app.insert_resource(Time::<Fixed>::from_hz(self.tick_rate))
+            .init_state::<GameState>()
+            .add_sub_state::<PlayPhase>()
+            .add_event::<FlapEvent>()
+            .add_event::<PipePassed>()
+            .add_event::<AudioEvent>()
+            .init_resource::<GameConfig>()
+            .init_resource::<GameData>()
+            .init_resource::<GameRng>()
+            .init_resource::<SimulationClock>()
+            .init_resource::<RunMode>()
+            .init_resource::<PlayfieldBounds>()
+            .init_resource::<CoursePlanner>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    attach_character_stats,
+                    replay_input_system.run_if(in_state(GameState::Replay)),
+                    bird_flap_system,
+                    bird_physics_system,
+                    difficulty_system,
+                    pipe_spawn_system,
+                    pipe_motion_system,
+                    scrolling_system,
+                    ground_scroll_system,
+                    attach_collider_shapes,
+                    collision_system,
+                    score_system,
+                    advance_simulation_clock,
+                )
+                    .chain()
+                    .in_set(SimulationSet)
+                    .run_if(simulation_running),
+            )
+            .add_systems(FixedUpdate, death_fall_system.run_if(in_state(GameState::Dying)))
+            .add_systems(
+                Update,
+                (
+                    start_on_first_flap.run_if(in_state(PlayPhase::Ready)),
+                    game_over_system.run_if(in_state(GameState::GameOver)),
+                ),
+            )
+            // 对局结束后保留撞击现场，结算界面显示在现场之上；开始下一局或返回菜单时才清理
+            .add_systems(OnEnter(GameState::Playing), (cleanup_game, setup_game).chain())
+            .add_systems(OnEnter(GameState::Replay), (cleanup_game, setup_replay, setup_game).chain())
+            .add_systems(OnExit(GameState::Replay), cleanup_replay)
+            .add_systems(OnEnter(GameState::Menu), cleanup_game)
+            .add_systems(OnEnter(GameState::Dying), on_bird_died);
+    }
+}
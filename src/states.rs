@@ -6,6 +6,27 @@ pub enum GameState {
     #[default]
     Menu,
     Playing,
+    Replay,
+    Dying,     // 小鸟撞击后坠落，世界冻结，结束后进入 GameOver
     GameOver,
     Leaderboard,
-}
\ No newline at end of file
+    Settings,
+}
+
+impl GameState {
+    // 是否正在进行一局（实时游戏或回放）
+    pub fn is_running(&self) -> bool {
+        matches!(self, GameState::Playing | GameState::Replay)
+    }
+}
+
+// 对局内的阶段，只在 Playing 状态下存在
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum PlayPhase {
+    #[default]
+    Ready,     // 准备阶段：小鸟悬停，第一次拍打后开始模拟
+    Running,
+    Paused,
+    Countdown, // 继续游戏前的 3-2-1 倒计时
+}
@@ -6,10 +6,22 @@ mod menu;
 mod gameplay;
 mod physics;
 mod ui;
+mod render;
+mod replay;
+mod ghost;
+mod pause;
+mod scenery;
+mod settings;
 
 // 重新导出所有系统函数
 pub use setup::*;
 pub use menu::*;
 pub use gameplay::*;
 pub use physics::*;
-pub use ui::*;
\ No newline at end of file
+pub use ui::*;
+pub use render::*;
+pub use replay::*;
+pub use ghost::*;
+pub use pause::*;
+pub use scenery::*;
+pub use settings::*;
\ No newline at end of file
@@ -1,84 +1,173 @@
 use bevy::prelude::*;
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::character::CharacterRegistry;
+use crate::collision::CollisionMasks;
 use crate::components::*;
+use crate::difficulty::DifficultyCurves;
+use crate::course::{CoursePhysics, CoursePlanner, GapPlan, BIRD_X, PIPE_SPAWN_X};
+use crate::events::{FlapEvent, PipePassed};
+use crate::replay::*;
 use crate::resources::*;
+use crate::settings::Settings;
 use crate::states::*;
 use crate::audio::AudioEvent;
 
 // ===== 游戏逻辑系统 =====
 
+// 障碍物贴图末端到通道边缘的距离
+const PIPE_GAP_CLEARANCE: f32 = 40.0;
+
+// 通道中心活动范围（±）的上下限
+// 节日装饰的缩放按上限计算，超过上限时障碍物无法覆盖到屏幕边缘
+const MIN_GAP_CENTER_RANGE: f32 = 10.0;
+const MAX_GAP_CENTER_RANGE: f32 = 100.0;
+
+// 最小通道高度
+const MIN_PIPE_GAP: f32 = 80.0;
+
+// 计分线在小鸟身后的距离
+const SCORE_LINE_OFFSET: f32 = 50.0;
+
 pub fn setup_game(
     mut commands: Commands,
-    assets: Res<GameAssets>,
+    state: Res<State<GameState>>,
     mut game_data: ResMut<GameData>,
+    mut config: ResMut<GameConfig>,
+    mut clock: ResMut<SimulationClock>,
+    mut rng: ResMut<GameRng>,
+    fixed_time: Res<Time<Fixed>>,
+    run_mode: Res<RunMode>,
+    playback: Option<Res<ReplayPlayback>>,
+    ghost_run: Option<Res<GhostRun>>,
+    masks: Option<Res<CollisionMasks>>,
+    characters: Option<Res<CharacterRegistry>>,
+    bounds: Res<PlayfieldBounds>,
+    mut planner: ResMut<CoursePlanner>,
 ) {
     game_data.score = 0;
+    *clock = SimulationClock::default();
+    *planner = CoursePlanner::default();
+    config.apply_difficulty(0.0);
+    config.pipe_spawn_timer.reset();
+
+    // 回放使用录制时的种子和角色；每日挑战使用日期种子和统一的角色；
+    // 有幽灵小鸟的普通对局使用最佳回放的种子和角色，否则优先使用固定种子，再否则随机生成
+    let (seed, character) = match playback {
+        Some(playback) if *state.get() == GameState::Replay => {
+            (playback.replay.seed, playback.replay.character.clone())
+        }
+        _ => {
+            commands.remove_resource::<ReplayPlayback>();
+            match (&*run_mode, ghost_run) {
+                (RunMode::Daily { date }, _) => (daily_seed(date), daily_character()),
+                (RunMode::Normal, Some(ghost_run)) => (ghost_run.0.seed, ghost_run.0.character.clone()),
+                (RunMode::Normal, None) => {
+                    (config.seed.unwrap_or_else(rand::random), game_data.selected_character.clone())
+                }
+            }
+        }
+    };
+    rng.reseed(seed);
 
-    // 生成小鸟 - 使用选中的角色和对应的缩放
-    let mut bird_entity = commands.spawn((
-        Sprite::from_image(assets.get_bird_texture(game_data.selected_character)),
-        Transform::from_translation(Vec3::new(-200.0, 0.0, 1.0))
-            .with_scale(Vec3::splat(game_data.selected_character.get_scale())),
-        Bird {
-            character: game_data.selected_character,
-        },
+    // 开始录制本局；实时对局开始前角色描述都已加载完成，只有直接启动的回放可能还没有
+    let manifest = characters.as_deref().and_then(|characters| characters.get(&character));
+    match manifest {
+        Some(manifest) => commands.insert_resource(ReplayRecorder(Replay::new(
+            seed,
+            fixed_time.timestep(),
+            ReplayConfig::from_config(&config),
+            manifest,
+        ))),
+        None => commands.remove_resource::<ReplayRecorder>(),
+    }
+
+    // 生成小鸟 - 使用选中的角色和对应的缩放，精灵由客户端附加
+    // 角色描述还没有加载完成时，属性和缩放由 attach_character_stats 补上
+    let mut bird = commands.spawn((
+        Transform::from_translation(Vec3::new(BIRD_X, 0.0, 1.0))
+            .with_scale(Vec3::splat(manifest.map_or(1.0, |manifest| manifest.scale))),
+        Bird { character },
         Velocity { y: 0.0 },
-        Gravity(980.0),
         Collider,
     ));
-    
-    // 如果角色支持动画，添加动画组件
-    if game_data.selected_character.has_animation() {
-        let animation_frames = assets.get_bird_animation_frames(game_data.selected_character);
-        bird_entity.insert(WingAnimation {
-            timer: Timer::from_seconds(0.15, TimerMode::Repeating), // 每帧0.15秒
-            current_frame: 1, // 从中间帧开始
-            frames: animation_frames,
-        });
-    }
-
-    // 生成背景山脉
-    for i in 0..5 {
-        commands.spawn((
-            Sprite::from_image(assets.mountain_texture.clone()),
-            Transform::from_translation(Vec3::new(
-                i as f32 * 200.0 - 400.0,
-                -250.0,
-                -1.0,
-            )),
-            Mountain,
-            Scrolling { speed: 50.0 },
-        ));
+    if let Some(manifest) = manifest {
+        bird.insert(manifest.stats);
+        if let Some(shape) = masks.as_deref().and_then(|masks| masks.bird_shape(manifest)) {
+            bird.insert(shape);
+        }
     }
 
-    // 生成云朵
-    for i in 0..3 {
-        commands.spawn((
-            Sprite::from_image(assets.cloud_texture.clone()),
-            Transform::from_translation(Vec3::new(
-                i as f32 * 300.0 - 300.0,
-                200.0,
-                -0.5,
-            ))
-            .with_scale(Vec3::splat(0.8)),
-            Cloud,
-            Scrolling { speed: 30.0 },
-        ));
-    }
+    // 生成地面，精灵由客户端附加
+    commands.spawn((Transform::from_translation(Vec3::new(0.0, bounds.floor, 0.5)), Ground));
 }
 
 pub fn bird_input_system(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
-    mut bird_query: Query<&mut Velocity, With<Bird>>,
-    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    mut flap_events: EventWriter<FlapEvent>,
+) {
+    if keyboard_input.just_pressed(settings.flap_key.key_code()) || mouse_input.just_pressed(MouseButton::Left) {
+        flap_events.write(FlapEvent);
+    }
+}
+
+// 准备阶段收到第一次拍打时开始本局
+// 拍打事件本身保留给 bird_flap_system，在第 0 个固定步生效
+pub fn start_on_first_flap(
+    mut flap_events: EventReader<FlapEvent>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    if !flap_events.is_empty() {
+        flap_events.clear();
+        next_phase.set(PlayPhase::Running);
+    }
+}
+
+pub fn bird_flap_system(
+    mut flap_events: EventReader<FlapEvent>,
+    mut bird_query: Query<(&mut Velocity, &CharacterStats), With<Bird>>,
+    clock: Res<SimulationClock>,
+    recorder: Option<ResMut<ReplayRecorder>>,
     mut audio_events: EventWriter<AudioEvent>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Space) || mouse_input.just_pressed(MouseButton::Left) {
-        for mut velocity in bird_query.iter_mut() {
-            velocity.y = config.jump_force;
-            audio_events.write(AudioEvent::Jump);
-        }
+    // 同一个固定步内的多次拍打只生效一次
+    if flap_events.is_empty() {
+        return;
+    }
+    flap_events.clear();
+
+    for (mut velocity, stats) in bird_query.iter_mut() {
+        velocity.y = stats.flap_impulse;
+        audio_events.write(AudioEvent::Jump);
+    }
+
+    // 记录拍打发生的固定步序号
+    if let Some(mut recorder) = recorder {
+        recorder.0.flaps.push(clock.tick);
+    }
+}
+
+// 每个固定步结束时推进本局时钟
+pub fn advance_simulation_clock(mut clock: ResMut<SimulationClock>) {
+    clock.tick += 1;
+}
+
+// 按难度曲线更新本局参数，已生成的管道对同步滚动速度
+pub fn difficulty_system(
+    time: Res<Time>,
+    clock: Res<SimulationClock>,
+    game_data: Res<GameData>,
+    mut config: ResMut<GameConfig>,
+    mut pair_query: Query<&mut Scrolling, With<PipePair>>,
+) {
+    let elapsed_secs = clock.tick as f32 * time.delta_secs();
+    let progress = config.difficulty.progress(game_data.score, elapsed_secs);
+    config.apply_difficulty(progress);
+    
+    for mut scrolling in pair_query.iter_mut() {
+        scrolling.speed = config.pipe_speed;
     }
 }
 
@@ -86,185 +175,291 @@ pub fn pipe_spawn_system(
     time: Res<Time>,
     mut commands: Commands,
     mut config: ResMut<GameConfig>,
-    assets: Res<GameAssets>,
-    game_data: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    masks: Res<CollisionMasks>,
+    clock: Res<SimulationClock>,
+    mut planner: ResMut<CoursePlanner>,
+    bird_query: Query<(&Transform, &ColliderShape, &CharacterStats), With<Bird>>,
 ) {
     config.pipe_spawn_timer.tick(time.delta());
     
     if config.pipe_spawn_timer.just_finished() {
-        let mut rng = thread_rng();
-        let gap_y = rng.gen_range(-100.0..100.0);
+        let rng = rng.gameplay();
+        let gap_center_range = config.gap_center_range.clamp(MIN_GAP_CENTER_RANGE, MAX_GAP_CENTER_RANGE);
+        let gap_y = rng.random_range(-gap_center_range..gap_center_range);
         
-        // 随机选择管道类型
-        let pipe_types = PipeType::all_types();
-        let selected_pipe_type = pipe_types[rng.gen_range(0..pipe_types.len())];
-        let pipe_texture = assets.get_pipe_texture(selected_pipe_type);
+        // 按障碍物主题随机选择管道类型
+        let pipe_types = config.pipe_theme.pipe_types();
+        let selected_pipe_type = pipe_types[rng.random_range(0..pipe_types.len())];
         let pipe_scale = selected_pipe_type.get_scale();
+        let pipe_shape = masks.pipe_shape(selected_pipe_type);
         
-        // 根据分数动态调整通道间隙大小
-        // 基础间隙150，每5分减少10像素，最小保持80像素
-        let gap_reduction = (game_data.score / 5) as f32 * 10.0;
-        let adjusted_gap = (config.pipe_gap - gap_reduction).max(80.0);
+        // 贴图末端与通道边缘保持固定距离，中心位置由贴图实际高度决定
+        let pipe_size = masks
+            .pipes
+            .get(&selected_pipe_type)
+            .map_or(Vec2::ZERO, |mask| mask.size());
+        let center_offset = pipe_size.y * pipe_scale / 2.0 + PIPE_GAP_CLEARANCE;
         
-        // 上管道 - 调整Y位置确保覆盖到屏幕顶部
-        commands.spawn((
-            Sprite::from_image(pipe_texture.clone()),
-            Transform::from_translation(Vec3::new(
-                500.0,
-                gap_y + adjusted_gap / 2.0 + 200.0, // 增加偏移量确保覆盖屏幕顶部
-                0.0,
-            ))
-            .with_rotation(Quat::from_rotation_z(std::f32::consts::PI))
-            .with_scale(Vec3::splat(pipe_scale)),
-            Pipe { pipe_type: selected_pipe_type },
-            Scrolling { speed: config.pipe_speed },
-            Collider,
-        ));
+        // 通道高度由难度曲线决定，最小保持80像素
+        let adjusted_gap = config.pipe_gap.max(MIN_PIPE_GAP);
+        
+        // 按难度选择运动模式，运动范围不会超出静止通道的范围，障碍物始终能覆盖到屏幕边缘
+        let (pattern, gap_y) = roll_pipe_motion(
+            rng,
+            config.motion_difficulty,
+            gap_y,
+            adjusted_gap / 2.0,
+            gap_center_range,
+        );
         
-        // 下管道 - 调整Y位置确保覆盖到屏幕底部
-        commands.spawn((
-            Sprite::from_image(pipe_texture),
-            Transform::from_translation(Vec3::new(
-                500.0,
-                gap_y - adjusted_gap / 2.0 - 200.0, // 增加偏移量确保覆盖屏幕底部
-                0.0,
+        // 按角色的实际物理参数修正通道，保证从上一对管道出发一定能够到达
+        let Ok((bird_transform, bird_shape, stats)) = bird_query.single() else {
+            return;
+        };
+        let physics = CoursePhysics {
+            gravity: stats.gravity,
+            jump_force: stats.flap_impulse,
+            terminal_velocity: stats.terminal_velocity,
+            timestep: time.delta_secs(),
+            pipe_speed: config.pipe_speed,
+            spawn_interval: config.pipe_spawn_timer.duration().as_secs_f32(),
+            bird_half_extents: CoursePhysics::bird_half_extents(bird_shape, bird_transform.scale),
+        };
+        let plan = planner.place(
+            &physics,
+            GapPlan {
+                pattern,
+                gap_y,
+                half_gap: adjusted_gap / 2.0,
+                half_width: pipe_shape
+                    .as_ref()
+                    .map_or(0.0, |shape| CoursePhysics::obstacle_half_width(shape, Vec3::splat(pipe_scale))),
+                spawn_time: clock.tick as f32 * time.delta_secs(),
+            },
+            gap_center_range,
+        );
+        let motion = PipeMotion {
+            pattern: plan.pattern,
+            gap_y: plan.gap_y,
+            half_gap: plan.half_gap,
+            elapsed: 0.0,
+        };
+        let (gap_y, half_gap) = motion.gap();
+        
+        // 管道对整体滚动和运动，子实体为上下障碍物和计分门
+        commands
+            .spawn((
+                Transform::from_translation(Vec3::new(PIPE_SPAWN_X, gap_y, 0.0)),
+                PipePair,
+                Scrolling { speed: config.pipe_speed, half_width: pipe_size.x * pipe_scale / 2.0 },
+                motion,
             ))
-            .with_scale(Vec3::splat(pipe_scale)),
-            Pipe { pipe_type: selected_pipe_type },
-            Scrolling { speed: config.pipe_speed },
-            Collider,
-        ));
+            .with_children(|pair| {
+                // 上障碍物 - 旋转180度，主体朝向通道，另一端延伸到屏幕顶部
+                let mut top_pipe = pair.spawn((
+                    Transform::from_translation(Vec3::new(0.0, half_gap + center_offset, 0.0))
+                        .with_rotation(Quat::from_rotation_z(std::f32::consts::PI))
+                        .with_scale(Vec3::splat(pipe_scale)),
+                    Pipe { pipe_type: selected_pipe_type },
+                    PipeOffset { side: 1.0, center_offset },
+                    Collider,
+                ));
+                if let Some(shape) = pipe_shape.clone() {
+                    top_pipe.insert(shape);
+                }
+                
+                // 下障碍物 - 另一端延伸到屏幕底部
+                let mut bottom_pipe = pair.spawn((
+                    Transform::from_translation(Vec3::new(0.0, -(half_gap + center_offset), 0.0))
+                        .with_scale(Vec3::splat(pipe_scale)),
+                    Pipe { pipe_type: selected_pipe_type },
+                    PipeOffset { side: -1.0, center_offset },
+                    Collider,
+                ));
+                if let Some(shape) = pipe_shape {
+                    bottom_pipe.insert(shape);
+                }
+                
+                // 计分门位于通道中心
+                pair.spawn((Transform::default(), ScoreGate::default()));
+            });
     }
 }
 
-pub fn scrolling_system(
-    time: Res<Time>,
-    mut commands: Commands,
-    mut query: Query<(Entity, &mut Transform, &Scrolling), (Without<Mountain>, Without<Cloud>)>,
-    mut mountain_query: Query<&mut Transform, (With<Mountain>, Without<Cloud>)>,
-    mut cloud_query: Query<&mut Transform, (With<Cloud>, Without<Mountain>)>,
-) {
-    // 处理普通滚动实体（管道等）
-    for (entity, mut transform, scrolling) in query.iter_mut() {
-        transform.translation.x -= scrolling.speed * time.delta_secs();
-        
-        // 移除超出屏幕的实体
-        if transform.translation.x < -600.0 {
-            commands.entity(entity).despawn();
+// 按难度权重随机选择运动模式，并把通道中心限制在运动后仍不越界的位置
+fn roll_pipe_motion(
+    rng: &mut StdRng,
+    max_difficulty: f32,
+    gap_y: f32,
+    half_gap: f32,
+    gap_center_range: f32,
+) -> (PipeMotionPattern, f32) {
+    let kinds = PipeMotionKind::all_kinds();
+    let total: f32 = kinds.iter().map(|kind| kind.spawn_weight(max_difficulty)).sum();
+    let mut roll = rng.random_range(0.0..total);
+    let kind = kinds
+        .into_iter()
+        .find(|kind| {
+            roll -= kind.spawn_weight(max_difficulty);
+            roll < 0.0
+        })
+        .unwrap_or(PipeMotionKind::Static);
+
+    match kind {
+        PipeMotionKind::Static => (PipeMotionPattern::Static, gap_y),
+        PipeMotionKind::Oscillate => {
+            let amplitude = rng.random_range(20.0f32..60.0).min(gap_center_range);
+            let period = rng.random_range(1.5..3.0);
+            let limit = gap_center_range - amplitude;
+            (PipeMotionPattern::Oscillate { amplitude, period }, gap_y.clamp(-limit, limit))
+        }
+        PipeMotionKind::Breathe => {
+            // 合拢后的通道不小于最小间隙
+            let amplitude = rng.random_range(15.0f32..35.0).min(half_gap - MIN_PIPE_GAP / 2.0);
+            let period = rng.random_range(1.5..3.0);
+            (PipeMotionPattern::Breathe { amplitude, period }, gap_y)
+        }
+        PipeMotionKind::Drift => {
+            let speed = rng.random_range(30.0..60.0);
+            let direction = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+            let pattern = PipeMotionPattern::Drift { speed: speed * direction, range: gap_center_range };
+            (pattern, gap_y)
         }
     }
-    
-    // 处理山脉循环滚动
-    for mut transform in mountain_query.iter_mut() {
-        transform.translation.x -= 50.0 * time.delta_secs();
+}
+
+// 按运动模式更新通道中心和上下障碍物的位置
+pub fn pipe_motion_system(
+    time: Res<Time>,
+    mut pair_query: Query<(&mut Transform, &mut PipeMotion, &Children), With<PipePair>>,
+    mut pipe_query: Query<(&mut Transform, &PipeOffset), Without<PipePair>>,
+) {
+    for (mut transform, mut motion, children) in pair_query.iter_mut() {
+        motion.elapsed += time.delta_secs();
+        let (gap_y, half_gap) = motion.gap();
+        transform.translation.y = gap_y;
         
-        // 当山脉移出左侧时，移动到屏幕右侧外并添加随机间隔
-        if transform.translation.x < -600.0 {
-            let mut rng = rand::thread_rng();
-            let random_gap = rng.gen_range(100.0..400.0); // 随机间隔100-400像素
-            // 移动到屏幕右侧外（600像素外）+ 基础间距 + 随机间隔
-            transform.translation.x = 600.0 + 200.0 + random_gap;
+        for child in children.iter() {
+            if let Ok((mut pipe_transform, offset)) = pipe_query.get_mut(child) {
+                pipe_transform.translation.y = offset.side * (half_gap + offset.center_offset);
+            }
         }
     }
-    
-    // 处理云朵循环滚动
-    for mut transform in cloud_query.iter_mut() {
-        transform.translation.x -= 30.0 * time.delta_secs();
-        
-        // 当云朵移出左侧时，移动到屏幕右侧外并添加随机间隔
-        if transform.translation.x < -600.0 {
-            let mut rng = rand::thread_rng();
-            let random_gap = rng.gen_range(200.0..600.0); // 随机间隔200-600像素
-            // 移动到屏幕右侧外（600像素外）+ 基础间距 + 随机间隔
-            transform.translation.x = 600.0 + 300.0 + random_gap;
+}
+
+// 地面与管道同速滚动，移过一个循环周期后回到原位；顶部高度跟随可活动区域
+pub fn ground_scroll_system(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    bounds: Res<PlayfieldBounds>,
+    mut query: Query<&mut Transform, With<Ground>>,
+) {
+    for mut transform in query.iter_mut() {
+        transform.translation.x -= config.pipe_speed * time.delta_secs();
+        if transform.translation.x <= -GROUND_SCROLL_WRAP {
+            transform.translation.x += GROUND_SCROLL_WRAP;
         }
+        transform.translation.y = bounds.floor;
     }
 }
 
-// 翅膀拍打动画系统
-pub fn wing_animation_system(
+// 按各自的 Scrolling 速度向左移动，整个移出屏幕左侧后移除
+pub fn scrolling_system(
     time: Res<Time>,
-    mut query: Query<(&mut WingAnimation, &mut Sprite)>,
+    bounds: Res<PlayfieldBounds>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &Scrolling)>,
 ) {
-    for (mut animation, mut sprite) in query.iter_mut() {
-        animation.timer.tick(time.delta());
+    for (entity, mut transform, scrolling) in query.iter_mut() {
+        transform.translation.x -= scrolling.speed * time.delta_secs();
         
-        if animation.timer.just_finished() {
-            // 循环播放动画帧：0 -> 1 -> 2 -> 1 -> 0 -> ...
-            animation.current_frame = match animation.current_frame {
-                0 => 1,
-                1 => 2,
-                2 => 1,
-                _ => 1, // 默认回到中间帧
-            };
-            
-            // 更新精灵纹理
-            if animation.current_frame < animation.frames.len() {
-                sprite.image = animation.frames[animation.current_frame].clone();
-            }
+        // 移除超出屏幕的实体
+        if transform.translation.x + scrolling.half_width < bounds.left {
+            commands.entity(entity).despawn();
         }
     }
 }
 
+// 计分门越过小鸟身后这条线时计分
 pub fn score_system(
     bird_query: Query<&Transform, With<Bird>>,
-    pipe_query: Query<&Transform, (With<Pipe>, Without<Bird>)>,
+    pair_query: Query<&Transform, With<PipePair>>,
+    mut gate_query: Query<(&Transform, &ChildOf, &mut ScoreGate)>,
     mut game_data: ResMut<GameData>,
+    mut passed_events: EventWriter<PipePassed>,
     mut audio_events: EventWriter<AudioEvent>,
 ) {
     for bird_transform in bird_query.iter() {
-        // 收集所有通过的管道x坐标，去重后计分
-        let mut scored_x_positions = std::collections::HashSet::new();
+        let score_line = bird_transform.translation.x - SCORE_LINE_OFFSET;
         
-        for pipe_transform in pipe_query.iter() {
-            // 如果小鸟通过了管道
-            if pipe_transform.translation.x < bird_transform.translation.x - 50.0
-                && pipe_transform.translation.x > bird_transform.translation.x - 55.0
-            {
-                // 将x坐标四舍五入到整数，确保同一对管道有相同的x坐标
-                let pipe_x = pipe_transform.translation.x.round() as i32;
-                scored_x_positions.insert(pipe_x);
+        for (gate_transform, child_of, mut gate) in gate_query.iter_mut() {
+            if gate.scored {
+                continue;
+            }
+            let Ok(pair_transform) = pair_query.get(child_of.parent()) else {
+                continue;
+            };
+            
+            // 只比较位置，与帧率、滚动速度和纵向运动无关
+            if pair_transform.mul_transform(*gate_transform).translation.x < score_line {
+                gate.scored = true;
+                game_data.score += 1;
+                passed_events.write(PipePassed {
+                    pair: child_of.parent(),
+                    score: game_data.score,
+                });
+                audio_events.write(AudioEvent::Score);
             }
-        }
-        
-        // 为每个唯一的x位置增加分数
-        for _ in scored_x_positions {
-            game_data.score += 1;
-            audio_events.write(AudioEvent::Score);
         }
     }
 }
 
 pub fn game_over_system(
     mut game_data: ResMut<GameData>,
+    run_mode: Res<RunMode>,
+    playback: Option<Res<ReplayPlayback>>,
 ) {
-    if game_data.score > game_data.high_score {
+    // 回放和每日挑战不计入最高分
+    let counts_for_high_score = playback.is_none() && *run_mode == RunMode::Normal;
+    if counts_for_high_score && game_data.score > game_data.high_score {
         game_data.high_score = game_data.score;
     }
 }
 
 pub fn restart_system(
+    mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    curves: Res<DifficultyCurves>,
+    mut config: ResMut<GameConfig>,
     mut next_state: ResMut<NextState<GameState>>,
     current_state: Res<State<GameState>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::KeyR) {
-        match current_state.get() {
-            GameState::GameOver => next_state.set(GameState::Playing),
-            _ => {}
-        }
+    // 重新开始总是普通对局，每日挑战只有一次机会
+    if keyboard_input.just_pressed(KeyCode::KeyR) && *current_state.get() == GameState::GameOver {
+        settings.apply_to(&mut config, &curves);
+        commands.insert_resource(RunMode::Normal);
+        next_state.set(GameState::Playing);
     }
     
+    // 游戏中的 ESC 用于暂停，由暂停系统处理
     if keyboard_input.just_pressed(KeyCode::Escape) {
         match current_state.get() {
-            GameState::Playing | GameState::GameOver | GameState::Leaderboard => next_state.set(GameState::Menu),
+            GameState::Replay | GameState::GameOver | GameState::Leaderboard => next_state.set(GameState::Menu),
             _ => {}
         }
     }
 }
 
-pub fn on_game_over(
+// 坠落到底后停留的秒数
+const DEATH_LINGER_SECS: f32 = 0.6;
+
+// 小鸟撞击后开始坠落
+pub fn on_bird_died(
+    mut commands: Commands,
     mut audio_events: EventWriter<AudioEvent>,
 ) {
+    commands.insert_resource(DeathLinger(Timer::from_seconds(DEATH_LINGER_SECS, TimerMode::Once)));
     audio_events.write(AudioEvent::Die);
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use crate::character::CharacterRegistry;
+use crate::collision::CollisionMasks;
+use crate::components::*;
+use crate::course::BIRD_X;
+use crate::replay::*;
+use crate::resources::*;
+use crate::settings::Settings;
+
+// ===== 幽灵小鸟系统 =====
+
+// 幽灵小鸟的透明度
+const GHOST_ALPHA: f32 = 0.4;
+
+// 选出本局幽灵小鸟重放的最佳回放，必须在 setup_game 之前运行，本局才能使用回放的种子和角色
+pub fn select_ghost_run(
+    mut commands: Commands,
+    characters: Option<Res<CharacterRegistry>>,
+    settings: Res<Settings>,
+    config: Res<GameConfig>,
+    run_mode: Res<RunMode>,
+    fixed_time: Res<Time<Fixed>>,
+    save_manager: Res<SaveManager>,
+) {
+    commands.remove_resource::<GhostRun>();
+    // 每日挑战的关卡由日期决定，不显示幽灵
+    if !settings.ghost_enabled || *run_mode != RunMode::Normal {
+        return;
+    }
+    let Ok(best) = Replay::load(&save_manager.replay_path(BEST_REPLAY)) else {
+        return;
+    };
+
+    // 参数或固定步长不同的回放无法重现同样的轨迹；
+    // 指定了种子时关卡由种子决定，只有种子相同的最佳回放才能在同一条关卡上重现
+    if best.ticks == 0
+        || best.timestep != fixed_time.timestep()
+        || best.config != ReplayConfig::from_config(&config)
+        || config.seed.is_some_and(|seed| seed != best.seed)
+    {
+        return;
+    }
+
+    // 幽灵使用录制时角色的属性，角色描述被删除或修改后无法重现
+    let reproducible = characters.is_some_and(|characters| best.check_character(&characters).is_ok());
+    if !reproducible {
+        return;
+    }
+    commands.insert_resource(GhostRun(best));
+}
+
+// 生成重放个人最佳回放的幽灵小鸟
+pub fn spawn_ghost(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    ghost_run: Option<Res<GhostRun>>,
+    characters: Option<Res<CharacterRegistry>>,
+    masks: Option<Res<CollisionMasks>>,
+) {
+    let Some(best) = ghost_run.map(|ghost_run| ghost_run.0.clone()) else {
+        return;
+    };
+    let Some(character) = characters.as_deref().and_then(|characters| characters.get(&best.character)) else {
+        return;
+    };
+    let transform = Transform::from_translation(Vec3::new(BIRD_X, 0.0, 0.9))
+        .with_scale(Vec3::splat(character.scale));
+    let mut ghost = commands.spawn((
+        Sprite {
+            color: Color::srgba(1.0, 1.0, 1.0, GHOST_ALPHA),
+            ..assets.get_bird_sprite(&character.id)
+        },
+        transform,
+        TransformInterpolation::new(transform),
+        GhostBird {
+            flaps: best.flaps,
+            next_flap: 0,
+            end_tick: best.ticks,
+        },
+        Velocity { y: 0.0 },
+        character.stats,
+        // 幽灵使用自己角色的动画
+        BirdAnimation::new(character.clips.clone()),
+    ));
+    
+    // 顶部阻挡时需要碰撞形状才能与真实小鸟一样贴着顶部滑行
+    if let Some(shape) = masks.as_deref().and_then(|masks| masks.bird_shape(character)) {
+        ghost.insert(shape);
+    }
+}
+
+// 在录制的固定步上让幽灵拍打翅膀，到达撞击时刻后消失
+pub fn ghost_flap_system(
+    mut commands: Commands,
+    clock: Res<SimulationClock>,
+    mut ghost_query: Query<(Entity, &mut GhostBird, &mut Velocity, &CharacterStats)>,
+) {
+    for (entity, mut ghost, mut velocity, stats) in ghost_query.iter_mut() {
+        if clock.tick >= ghost.end_tick {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let ghost = &mut *ghost;
+        if take_flap(&ghost.flaps, &mut ghost.next_flap, clock.tick) {
+            velocity.y = stats.flap_impulse;
+        }
+    }
+}
+
+pub fn cleanup_ghost(mut commands: Commands, query: Query<Entity, With<GhostBird>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,16 +1,29 @@
 use bevy::prelude::*;
 use bevy::input::mouse::MouseWheel;
 use crate::components::*;
+use crate::difficulty::DifficultyCurves;
+use crate::locale::Locale;
+use crate::settings::Settings;
+use crate::replay::*;
 use crate::resources::*;
 use crate::states::*;
 use crate::audio::AudioEvent;
+use crate::character::{CharacterManifest, CharacterRegistry};
+use crate::collision::CollisionMasks;
 
 // ===== 菜单系统 =====
 
+// 经典小鸟达到最大倾角时的竖直速度，角色的转向灵敏度以它为基准
+const CLASSIC_TILT_VELOCITY: f32 = 300.0;
+
 pub fn setup_menu_when_ready(
     mut commands: Commands,
     assets: Option<Res<GameAssets>>,
+    characters: Option<Res<CharacterRegistry>>,
+    masks: Option<Res<CollisionMasks>>,
     game_data: Res<GameData>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
     existing_menu: Query<&MenuText>,
 ) {
     // 检查菜单是否已经设置
@@ -18,8 +31,8 @@ pub fn setup_menu_when_ready(
         return;
     }
     
-    // 检查资源是否已加载
-    let Some(assets) = assets else {
+    // 检查资源是否已加载，角色属性中的碰撞框尺寸来自碰撞遮罩
+    let (Some(assets), Some(characters), Some(masks)) = (assets, characters, masks) else {
         return;
     };
     
@@ -27,7 +40,7 @@ pub fn setup_menu_when_ready(
     commands.spawn((
         Text2d::new("Flappy Bird"),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 50.0,
             ..default()
         },
@@ -36,57 +49,169 @@ pub fn setup_menu_when_ready(
         MenuText,
     ));
     
+    // 角色名称、预览、属性和排行榜
+    if let Some(character) = characters.get(&game_data.selected_character) {
+        spawn_character_panel(&mut commands, &assets, &game_data, &locale, character, &masks);
+    }
+    
+    // 左侧每日挑战标题
+    commands.spawn((
+        Text2d::new(locale.get("menu.daily_title")),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 30.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.8, 0.0)),
+        Transform::from_translation(Vec3::new(-280.0, 110.0, 1.0)),
+        MenuText,
+    ));
+    
+    // 今日状态
+    let date = today();
+    let today_text = match game_data.save_data.daily_entry(&date) {
+        Some(entry) => locale.format("menu.daily_done", &[("score", &entry.score)]),
+        None => locale.get("menu.daily_start").to_string(),
+    };
+    commands.spawn((
+        Text2d::new(today_text),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        Transform::from_translation(Vec3::new(-280.0, 65.0, 1.0)),
+        MenuText,
+    ));
+    
+    // 最近几天的成绩
+    for (i, entry) in game_data.save_data.daily.iter().filter(|entry| entry.date != date).take(4).enumerate() {
+        commands.spawn((
+            Text2d::new(locale.format("menu.daily_entry", &[("date", &entry.date), ("score", &entry.score)])),
+            TextFont {
+                font: assets.font(locale.language()),
+                font_size: 19.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            Transform::from_translation(Vec3::new(-280.0, 35.0 - i as f32 * 30.0, 1.0)),
+            MenuText,
+        ));
+    }
+    
+    // 控制说明
+    commands.spawn((
+        Text2d::new(locale.get("menu.controls")),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        Transform::from_translation(Vec3::new(0.0, -120.0, 1.0)),
+        MenuText,
+    ));
+    
+    // 设置入口和当前难度
+    commands.spawn((
+        Text2d::new(difficulty_label(&locale, &settings)),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        Transform::from_translation(Vec3::new(0.0, -200.0, 1.0)),
+        DifficultyText,
+        MenuText,
+    ));
+}
+
+// 设置入口和当前难度的提示文字
+fn difficulty_label(locale: &Locale, settings: &Settings) -> String {
+    locale.format("menu.settings", &[("difficulty", &locale.get(settings.difficulty.locale_key()))])
+}
+
+// 随选中角色变化的菜单内容：角色名称、预览、属性和该角色的排行榜
+fn spawn_character_panel(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    game_data: &GameData,
+    locale: &Locale,
+    character: &CharacterManifest,
+    masks: &CollisionMasks,
+) {
+    let name = character.display_name(locale.code());
+    
     // 当前角色显示
     commands.spawn((
-        Text2d::new(format!("当前角色: {}", game_data.selected_character.get_name())),
+        Text2d::new(locale.format("menu.character", &[("name", &name)])),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 28.0,
             ..default()
         },
         TextColor(Color::srgb(0.9, 0.9, 0.9)),
         Transform::from_translation(Vec3::new(0.0, 80.0, 1.0)),
+        CharacterPanel,
         MenuText,
     ));
     
     // 角色预览
     commands.spawn((
-        Sprite::from_image(assets.get_bird_texture(game_data.selected_character)),
+        Sprite::from_image(assets.get_bird_texture(&character.id)),
         Transform::from_translation(Vec3::new(0.0, 20.0, 1.0))
-            .with_scale(Vec3::splat(game_data.selected_character.get_scale())),
-        CharacterPreview,
+            .with_scale(Vec3::splat(character.scale)),
+        CharacterPanel,
+        MenuText,
+    ));
+    
+    // 角色属性
+    commands.spawn((
+        Text2d::new(character_stats_label(locale, character, masks)),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.9, 1.0)),
+        Transform::from_translation(Vec3::new(0.0, -35.0, 1.0)),
+        CharacterPanel,
         MenuText,
     ));
     
     // 右侧排行榜标题
     commands.spawn((
-        Text2d::new("排行榜"),
+        Text2d::new(locale.format("menu.leaderboard_title", &[("name", &name)])),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 30.0,
             ..default()
         },
         TextColor(Color::srgb(1.0, 0.8, 0.0)),
         Transform::from_translation(Vec3::new(280.0, 110.0, 1.0)),
+        CharacterPanel,
         MenuText,
     ));
     
-    // 右侧排行榜内容
-    let leaderboard = &game_data.save_data.leaderboard;
+    // 右侧排行榜内容，只显示当前角色的成绩
+    let leaderboard: Vec<_> = game_data.save_data.leaderboard_for(&character.id).take(5).collect();
     if leaderboard.is_empty() {
         commands.spawn((
-            Text2d::new("暂无记录\n开始游戏创建记录吧!"),
+            Text2d::new(locale.get("menu.leaderboard_empty")),
             TextFont {
-                font: assets.font.clone(),
+                font: assets.font(locale.language()),
                 font_size: 20.0,
                 ..default()
             },
             TextColor(Color::srgb(0.8, 0.8, 0.8)),
             Transform::from_translation(Vec3::new(280.0, 30.0, 1.0)),
+            CharacterPanel,
             MenuText,
         ));
     } else {
-        for (i, entry) in leaderboard.iter().enumerate().take(5) {
+        for (i, entry) in leaderboard.into_iter().enumerate() {
             let rank_color = match i {
                 0 => Color::srgb(1.0, 0.8, 0.0), // 金色
                 1 => Color::srgb(0.8, 0.8, 0.8), // 银色
@@ -94,109 +219,185 @@ pub fn setup_menu_when_ready(
                 _ => Color::srgb(0.9, 0.9, 0.9),
             };
             
-            let rank_text = match i {
-                0 => "第1名".to_string(),
-                1 => "第2名".to_string(),
-                2 => "第3名".to_string(),
-                _ => format!("第{}名", i + 1),
-            };
-            
             commands.spawn((
-                Text2d::new(format!(
-                    "{} {} - {} 分",
-                    rank_text,
-                    entry.character.get_name(),
-                    entry.score
-                )),
+                Text2d::new(locale.format("menu.leaderboard_entry", &[("rank", &(i + 1)), ("score", &entry.score)])),
                 TextFont {
-                    font: assets.font.clone(),
+                    font: assets.font(locale.language()),
                     font_size: 19.0,
                     ..default()
                 },
                 TextColor(rank_color),
                 Transform::from_translation(Vec3::new(280.0, 65.0 - i as f32 * 30.0, 1.0)),
+                CharacterPanel,
                 MenuText,
             ));
         }
     }
-    
-    // 控制说明
-    commands.spawn((
-        Text2d::new("← → 或滚轮切换角色\n\n空格键或鼠标左键开始游戏"),
-        TextFont {
-            font: assets.font.clone(),
-            font_size: 24.0,
-            ..default()
-        },
-        TextColor(Color::srgb(0.8, 0.8, 0.8)),
-        Transform::from_translation(Vec3::new(0.0, -120.0, 1.0)),
-        MenuText,
-    ));
+}
+
+// 角色属性的说明文字，碰撞框按贴图实际尺寸换算为像素
+fn character_stats_label(locale: &Locale, character: &CharacterManifest, masks: &CollisionMasks) -> String {
+    let stats = character.stats;
+    let hitbox = masks
+        .bird_shape(character)
+        .and_then(|shape| shape.segments.first().map(|segment| segment.size() * character.scale))
+        .unwrap_or_default();
+    // 转向灵敏度以经典小鸟为 100%
+    let tilt_response = CLASSIC_TILT_VELOCITY / stats.tilt_velocity * 100.0;
+    locale.format(
+        "menu.character_stats",
+        &[
+            ("gravity", &format!("{:.0}", stats.gravity)),
+            ("flap", &format!("{:.0}", stats.flap_impulse)),
+            ("terminal", &format!("{:.0}", stats.terminal_velocity)),
+            ("width", &format!("{:.0}", hitbox.x)),
+            ("height", &format!("{:.0}", hitbox.y)),
+            ("tilt", &format!("{:.0}", tilt_response)),
+        ],
+    )
 }
 
 pub fn menu_system(
+    mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    mut game_data: ResMut<GameData>,
+    settings: Res<Settings>,
+    curves: Res<DifficultyCurves>,
+    mut config: ResMut<GameConfig>,
+    save_manager: Res<SaveManager>,
     mut next_state: ResMut<NextState<GameState>>,
     mut audio_events: EventWriter<AudioEvent>,
 ) {
     if keyboard_input.just_pressed(KeyCode::Space) || mouse_input.just_pressed(MouseButton::Left) {
+        settings.apply_to(&mut config, &curves);
+        commands.insert_resource(RunMode::Normal);
+        audio_events.write(AudioEvent::Swoosh);
+        next_state.set(GameState::Playing);
+    }
+    
+    // 每日挑战：每天只有一次机会，开始时立即登记，中途退出按当时分数计
+    if keyboard_input.just_pressed(KeyCode::KeyD) {
+        let date = today();
+        if game_data.save_data.daily_entry(&date).is_some() {
+            return;
+        }
+        
+        game_data.save_data = save_manager.record_daily_result(
+            game_data.save_data.clone(),
+            &date,
+            0,
+            daily_character(),
+        );
+        if let Err(e) = save_manager.save_data(&game_data.save_data) {
+            eprintln!("保存数据失败: {}", e);
+        }
+        
+        // 每日挑战的关卡不受玩家设置和角色影响，同一天所有人相同
+        config.use_daily_rules(&curves);
+        commands.insert_resource(RunMode::Daily { date });
         audio_events.write(AudioEvent::Swoosh);
         next_state.set(GameState::Playing);
     }
     
+    if keyboard_input.just_pressed(KeyCode::KeyS) {
+        audio_events.write(AudioEvent::Swoosh);
+        next_state.set(GameState::Settings);
+    }
+    
     if keyboard_input.just_pressed(KeyCode::KeyL) {
         audio_events.write(AudioEvent::Swoosh);
         next_state.set(GameState::Leaderboard);
     }
+    
+    // 观看上一局回放
+    if keyboard_input.just_pressed(KeyCode::KeyV) {
+        match Replay::load(&save_manager.replay_path(LAST_REPLAY)) {
+            Ok(replay) => {
+                commands.insert_resource(ReplayPlayback::new(replay));
+                audio_events.write(AudioEvent::Swoosh);
+                next_state.set(GameState::Replay);
+            }
+            Err(e) => eprintln!("加载回放失败: {}", e),
+        }
+    }
 }
 
 pub fn character_selection_system(
+    mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut game_data: ResMut<GameData>,
     assets: Option<Res<GameAssets>>,
+    characters: Option<Res<CharacterRegistry>>,
+    masks: Option<Res<CollisionMasks>>,
+    locale: Res<Locale>,
     mut scroll_events: EventReader<MouseWheel>,
-    mut text_query: Query<&mut Text2d, With<MenuText>>,
-    mut preview_query: Query<(&mut Sprite, &mut Transform), With<CharacterPreview>>,
+    panel_query: Query<Entity, With<CharacterPanel>>,
 ) {
-    let mut character_changed = false;
+    // 角色列表还没有加载完成时不能切换
+    let Some(characters) = characters else {
+        scroll_events.clear();
+        return;
+    };
+    let current = game_data.selected_character.clone();
+    let mut selected = current.clone();
     
     // 键盘输入
     if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
-        game_data.selected_character = game_data.selected_character.previous();
-        character_changed = true;
+        selected = characters.previous(&selected);
     } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
-        game_data.selected_character = game_data.selected_character.next();
-        character_changed = true;
+        selected = characters.next(&selected);
     }
     
     // 鼠标滚轮输入
     for scroll in scroll_events.read() {
         if scroll.y > 0.0 {
-            game_data.selected_character = game_data.selected_character.next();
-            character_changed = true;
+            selected = characters.next(&selected);
         } else if scroll.y < 0.0 {
-            game_data.selected_character = game_data.selected_character.previous();
-            character_changed = true;
+            selected = characters.previous(&selected);
         }
     }
     
-    // 更新UI
-    if character_changed {
-        // 检查assets是否可用
-        if let Some(assets) = assets {
-            // 更新角色名称文本
-            for mut text in text_query.iter_mut() {
-                if text.0.contains("当前角色") {
-                    **text = format!("当前角色: {}", game_data.selected_character.get_name());
-                }
-            }
-            
-            // 更新角色预览
-            for (mut sprite, mut transform) in preview_query.iter_mut() {
-                *sprite = Sprite::from_image(assets.get_bird_texture(game_data.selected_character));
-                transform.scale = Vec3::splat(game_data.selected_character.get_scale());
-            }
+    if selected == current {
+        return;
+    }
+    game_data.selected_character = selected;
+    
+    // 重新生成角色相关的菜单内容，菜单还没有生成时由 setup_menu_when_ready 负责
+    if panel_query.is_empty() {
+        return;
+    }
+    if let (Some(assets), Some(masks), Some(character)) = (assets, masks, characters.get(&game_data.selected_character)) {
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn();
         }
+        spawn_character_panel(&mut commands, &assets, &game_data, &locale, character, &masks);
+    }
+}
+
+// 切换难度预设，立即保存，下一局开始时应用
+pub fn difficulty_selection_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    save_manager: Res<SaveManager>,
+    locale: Res<Locale>,
+    mut audio_events: EventWriter<AudioEvent>,
+    mut text_query: Query<&mut Text2d, With<DifficultyText>>,
+) {
+    let difficulty = if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        settings.difficulty.next()
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        settings.difficulty.previous()
+    } else {
+        return;
+    };
+
+    settings.difficulty = difficulty;
+    if let Err(e) = save_manager.save_settings(&settings) {
+        eprintln!("保存设置失败: {}", e);
+    }
+    audio_events.write(AudioEvent::Swoosh);
+    for mut text in text_query.iter_mut() {
+        **text = difficulty_label(&locale, &settings);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use crate::components::*;
+use crate::difficulty::DifficultyCurves;
+use crate::locale::Locale;
+use crate::settings::Settings;
+use crate::resources::*;
+use crate::states::*;
+use crate::audio::AudioEvent;
+
+// ===== 暂停系统 =====
+
+// 继续游戏前的倒计时秒数
+const RESUME_COUNTDOWN_SECS: f32 = 3.0;
+
+// 记录窗口焦点：在所有状态下读取焦点事件，
+// 暂停期间或菜单中产生的焦点事件不会留到继续游戏之后才被处理
+pub fn track_window_focus(mut focus_events: EventReader<WindowFocused>, mut focus: ResMut<WindowFocus>) {
+    // 以最后一次焦点变化为准
+    if let Some(event) = focus_events.read().last() {
+        focus.0 = event.focused;
+    }
+}
+
+// 按 P/ESC 或窗口没有焦点时暂停
+pub fn pause_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<WindowFocus>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+    mut audio_events: EventWriter<AudioEvent>,
+) {
+    if !focus.0
+        || keyboard_input.just_pressed(KeyCode::KeyP)
+        || keyboard_input.just_pressed(KeyCode::Escape)
+    {
+        audio_events.write(AudioEvent::Swoosh);
+        next_phase.set(PlayPhase::Paused);
+    }
+}
+
+// 暂停菜单：继续、重新开始或返回菜单
+pub fn pause_menu_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    clock: Res<SimulationClock>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut audio_events: EventWriter<AudioEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) || keyboard_input.just_pressed(KeyCode::Escape) {
+        // 还没开始飞行时回到准备阶段，否则倒计时后继续
+        if clock.tick == 0 {
+            next_phase.set(PlayPhase::Ready);
+        } else {
+            next_phase.set(PlayPhase::Countdown);
+        }
+    } else if keyboard_input.just_pressed(KeyCode::KeyR) {
+        audio_events.write(AudioEvent::Swoosh);
+        commands.queue(restart_run);
+    } else if keyboard_input.just_pressed(KeyCode::KeyQ) {
+        audio_events.write(AudioEvent::Swoosh);
+        next_state.set(GameState::Menu);
+    }
+}
+
+// 重新开始当前对局
+// 同状态切换不会触发 OnExit/OnEnter，这里手动运行这两个调度，
+// 复用所有插件注册的清理和初始化系统
+pub fn restart_run(world: &mut World) {
+    world.run_schedule(OnExit(GameState::Playing));
+    // 重新开始总是普通对局，每日挑战只有一次机会
+    world.insert_resource(RunMode::Normal);
+    let settings = world.get_resource::<Settings>().cloned();
+    let curves = world.get_resource::<DifficultyCurves>().cloned();
+    if let (Some(settings), Some(curves)) = (settings, curves) {
+        settings.apply_to(&mut world.resource_mut::<GameConfig>(), &curves);
+    }
+    world.run_schedule(OnEnter(GameState::Playing));
+    world.resource_mut::<NextState<PlayPhase>>().set(PlayPhase::Ready);
+}
+
+pub fn setup_pause_overlay(mut commands: Commands, assets: Res<GameAssets>, locale: Res<Locale>) {
+    // 半透明黑色蒙版
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            custom_size: Some(Vec2::new(800.0, 600.0)),
+            ..default()
+        },
+        Transform::from_translation(Vec3::new(0.0, 0.0, 5.0)),
+        PauseOverlay,
+    ));
+    
+    commands.spawn((
+        Text2d::new(locale.get("pause.title")),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 48.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.8, 0.2)),
+        Transform::from_translation(Vec3::new(0.0, 80.0, 6.0)),
+        PauseOverlay,
+    ));
+    
+    commands.spawn((
+        Text2d::new(locale.get("pause.help")),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 26.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        Transform::from_translation(Vec3::new(0.0, -50.0, 6.0)),
+        PauseOverlay,
+    ));
+}
+
+pub fn cleanup_pause_overlay(mut commands: Commands, query: Query<Entity, With<PauseOverlay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn setup_countdown(mut commands: Commands, assets: Res<GameAssets>, locale: Res<Locale>) {
+    commands.insert_resource(ResumeCountdown(Timer::from_seconds(RESUME_COUNTDOWN_SECS, TimerMode::Once)));
+    
+    commands.spawn((
+        Text2d::new(format!("{}", RESUME_COUNTDOWN_SECS as u32)),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 96.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_translation(Vec3::new(0.0, 0.0, 6.0)),
+        CountdownText,
+    ));
+}
+
+// 倒计时结束后继续游戏
+pub fn countdown_system(
+    time: Res<Time>,
+    mut countdown: ResMut<ResumeCountdown>,
+    mut text_query: Query<&mut Text2d, With<CountdownText>>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    countdown.0.tick(time.delta());
+    
+    if countdown.0.finished() {
+        next_phase.set(PlayPhase::Running);
+        return;
+    }
+    
+    let seconds_left = countdown.0.remaining_secs().ceil() as u32;
+    for mut text in text_query.iter_mut() {
+        **text = seconds_left.to_string();
+    }
+}
+
+pub fn cleanup_countdown(mut commands: Commands, query: Query<Entity, With<CountdownText>>) {
+    commands.remove_resource::<ResumeCountdown>();
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
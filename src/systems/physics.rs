@@ -1,5 +1,8 @@
 use bevy::prelude::*;
+use crate::character::CharacterRegistry;
+use crate::collision::{masks_overlap, overlap_regions, world_rect, CollisionMasks};
 use crate::components::*;
+use crate::resources::{DeathLinger, GameConfig, PlayfieldBounds, SimulationClock};
 use crate::states::*;
 use crate::audio::AudioEvent;
 
@@ -7,125 +10,177 @@ use crate::audio::AudioEvent;
 
 pub fn bird_physics_system(
     time: Res<Time>,
-    mut bird_query: Query<(&mut Transform, &mut Velocity, &Gravity), With<Bird>>,
+    config: Res<GameConfig>,
+    bounds: Res<PlayfieldBounds>,
+    mut bird_query: Query<
+        (&mut Transform, &mut Velocity, &CharacterStats, Option<&ColliderShape>),
+        Or<(With<Bird>, With<GhostBird>)>,
+    >,
 ) {
-    for (mut transform, mut velocity, gravity) in bird_query.iter_mut() {
-        // 应用重力
-        velocity.y -= gravity.0 * time.delta_secs();
+    for (mut transform, mut velocity, stats, shape) in bird_query.iter_mut() {
+        // 应用重力，下落速度不超过角色的最大下落速度
+        velocity.y = (velocity.y - stats.gravity * time.delta_secs()).max(-stats.terminal_velocity);
         
         // 更新位置
         transform.translation.y += velocity.y * time.delta_secs();
         
         // 限制小鸟旋转角度
-        let angle = (velocity.y / 300.0).clamp(-1.0, 1.0) * 0.5;
+        let angle = (velocity.y / stats.tilt_velocity).clamp(-1.0, 1.0) * BIRD_MAX_TILT;
         transform.rotation = Quat::from_rotation_z(angle);
+        
+        // 顶部阻挡时小鸟贴着顶部滑行，幽灵也要同样处理才能重现录制的轨迹
+        if config.ceiling == CeilingMode::Clamp {
+            let top = vertical_extent(&transform, shape).1;
+            if top > bounds.ceiling {
+                transform.translation.y -= top - bounds.ceiling;
+                velocity.y = velocity.y.min(0.0);
+            }
+        }
+    }
+}
+
+// 撞击后小鸟转为头朝下的角速度（弧度/秒）
+const DEATH_SPIN_SPEED: f32 = 8.0;
+
+// 撞击后的坠落：世界已经冻结，小鸟在固定步上继续下落并转为头朝下，落到地面停留片刻后显示结算
+pub fn death_fall_system(
+    time: Res<Time>,
+    bounds: Res<PlayfieldBounds>,
+    ground_query: Query<&Transform, (With<Ground>, Without<Bird>)>,
+    mut linger: ResMut<DeathLinger>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut bird_query: Query<(&mut Transform, &mut Velocity, &CharacterStats, Option<&ColliderShape>), With<Bird>>,
+) {
+    let mut grounded = true;
+    for (mut transform, mut velocity, stats, shape) in bird_query.iter_mut() {
+        let angle = transform.rotation.to_euler(EulerRot::XYZ).2;
+        let angle = (angle - DEATH_SPIN_SPEED * time.delta_secs()).max(-std::f32::consts::FRAC_PI_2);
+        transform.rotation = Quat::from_rotation_z(angle);
+
+        // 撞击时正在上升的小鸟直接开始下落
+        velocity.y = (velocity.y.min(0.0) - stats.gravity * time.delta_secs()).max(-stats.terminal_velocity);
+        transform.translation.y += velocity.y * time.delta_secs();
+
+        // 落到地面后停住，转身时不穿过地面
+        let floor = ground_top(&ground_query, &bounds);
+        let bottom = vertical_extent(&transform, shape).0;
+        if bottom < floor {
+            transform.translation.y += floor - bottom;
+            velocity.y = 0.0;
+        } else {
+            grounded = false;
+        }
+    }
+
+    if grounded {
+        linger.0.tick(time.delta());
+        if linger.0.finished() {
+            next_state.set(GameState::GameOver);
+        }
+    }
+}
+
+// 地面顶部的高度，地面还没有生成时使用可活动区域的下边界
+fn ground_top(ground_query: &Query<&Transform, (With<Ground>, Without<Bird>)>, bounds: &PlayfieldBounds) -> f32 {
+    ground_query.iter().next().map_or(bounds.floor, |ground| ground.translation.y)
+}
+
+// 小鸟在世界坐标中的下沿和上沿，使用碰撞形状，形状未就绪时使用中心点
+fn vertical_extent(transform: &Transform, shape: Option<&ColliderShape>) -> (f32, f32) {
+    match shape {
+        Some(shape) => shape
+            .segments
+            .iter()
+            .map(|segment| world_rect(transform, *segment))
+            .fold((f32::MAX, f32::MIN), |(bottom, top), rect| {
+                (bottom.min(rect.min.y), top.max(rect.max.y))
+            }),
+        None => (transform.translation.y, transform.translation.y),
+    }
+}
+
+// 给还没有角色属性的小鸟补上属性和缩放
+// 角色描述晚于小鸟加载完成时（例如直接启动回放）在这里补齐，排在拍打之前，第 0 个固定步就能生效
+pub fn attach_character_stats(
+    mut commands: Commands,
+    characters: Res<CharacterRegistry>,
+    mut query: Query<(Entity, &Bird, &mut Transform), Without<CharacterStats>>,
+) {
+    for (entity, bird, mut transform) in query.iter_mut() {
+        if let Some(character) = characters.get(&bird.character) {
+            transform.scale = Vec3::splat(character.scale);
+            commands.entity(entity).insert(character.stats);
+        }
+    }
+}
+
+// 给还没有碰撞形状的实体补上形状
+// 正常情况下形状在生成时就已确定；贴图晚于实体加载完成时（例如直接启动回放）在这里补齐
+pub fn attach_collider_shapes(
+    mut commands: Commands,
+    masks: Res<CollisionMasks>,
+    characters: Res<CharacterRegistry>,
+    query: Query<(Entity, Option<&Bird>, Option<&Pipe>), (With<Collider>, Without<ColliderShape>)>,
+) {
+    for (entity, bird, pipe) in query.iter() {
+        let shape = match (bird, pipe) {
+            (Some(bird), _) => characters.get(&bird.character).and_then(|character| masks.bird_shape(character)),
+            (_, Some(pipe)) => masks.pipe_shape(pipe.pipe_type),
+            _ => None,
+        };
+        if let Some(shape) = shape {
+            commands.entity(entity).insert(shape);
+        }
     }
 }
 
 pub fn collision_system(
-    bird_query: Query<(&Transform, &Bird), With<Collider>>,
-    pipe_query: Query<(&Transform, &Pipe), (With<Collider>, Without<Bird>)>,
+    bird_query: Query<(&Transform, &Bird, Option<&ColliderShape>), With<Collider>>,
+    pipe_query: Query<(&Transform, &Pipe, &ColliderShape, &ChildOf), (With<Collider>, Without<Bird>)>,
+    pair_query: Query<&Transform, (With<PipePair>, Without<Bird>)>,
+    ground_query: Query<&Transform, (With<Ground>, Without<Bird>)>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut clock: ResMut<SimulationClock>,
+    config: Res<GameConfig>,
+    bounds: Res<PlayfieldBounds>,
+    masks: Res<CollisionMasks>,
     mut audio_events: EventWriter<AudioEvent>,
 ) {
-    for (bird_transform, bird) in bird_query.iter() {
-        let bird_radius = bird.character.get_collision_radius();
-        
-        // 检查边界碰撞
-        if bird_transform.translation.y - bird_radius < -280.0 
-            || bird_transform.translation.y + bird_radius > 280.0 {
+    for (bird_transform, bird, bird_shape) in bird_query.iter() {
+        // 检查地面和顶部碰撞 - 使用碰撞形状的上下沿，顶部阻挡时由物理系统处理
+        let (bird_bottom, bird_top) = vertical_extent(bird_transform, bird_shape);
+        let hit_ceiling = config.ceiling == CeilingMode::Kill && bird_top > bounds.ceiling;
+        if bird_bottom < ground_top(&ground_query, &bounds) || hit_ceiling {
             audio_events.write(AudioEvent::Hit);
-            next_state.set(GameState::GameOver);
+            clock.crashed = true;
+            next_state.set(GameState::Dying);
             return;
         }
-        
-        // 改进的管道碰撞检测
-        for (pipe_transform, pipe) in pipe_query.iter() {
-            if check_pipe_collision(bird_transform, bird, pipe_transform, pipe) {
+
+        let Some(bird_shape) = bird_shape else {
+            continue;
+        };
+
+        // 管道碰撞检测：碰撞形状只做粗略筛选，再在重叠区域内做像素级检测
+        // 碰撞形状由遮罩生成，有碰撞形状的小鸟和管道一定有对应的遮罩
+        for (pipe_transform, pipe, pipe_shape, child_of) in pipe_query.iter() {
+            let Some((bird_mask, pipe_mask)) = masks.get(&bird.character, pipe.pipe_type) else {
+                continue;
+            };
+            // 管道的变换相对于管道对，GlobalTransform 只在渲染前更新，这里直接计算世界变换
+            let Ok(pair_transform) = pair_query.get(child_of.parent()) else {
+                continue;
+            };
+            let pipe_transform = &pair_transform.mul_transform(*pipe_transform);
+            let collided = overlap_regions(bird_transform, bird_shape, pipe_transform, pipe_shape)
+                .into_iter()
+                .any(|region| masks_overlap(bird_transform, bird_mask, pipe_transform, pipe_mask, region));
+            if collided {
                 audio_events.write(AudioEvent::Hit);
-                next_state.set(GameState::GameOver);
+                clock.crashed = true;
+                next_state.set(GameState::Dying);
                 return;
             }
         }
     }
 }
-
-// 专门的管道碰撞检测函数
-fn check_pipe_collision(
-    bird_transform: &Transform,
-    bird: &Bird,
-    pipe_transform: &Transform,
-    pipe: &Pipe,
-) -> bool {
-    let bird_radius = bird.character.get_collision_radius();
-    let pipe_scale = pipe.pipe_type.get_scale();
-    let (base_offset_x, base_offset_y) = pipe.pipe_type.get_collision_offset();
-    
-    // 小鸟边界
-    let bird_left = bird_transform.translation.x - bird_radius;
-    let bird_right = bird_transform.translation.x + bird_radius;
-    let bird_bottom = bird_transform.translation.y - bird_radius;
-    let bird_top = bird_transform.translation.y + bird_radius;
-    
-    // 管道碰撞中心（考虑偏移）
-    let pipe_center_x = pipe_transform.translation.x + base_offset_x * pipe_scale;
-    let pipe_center_y = pipe_transform.translation.y + base_offset_y * pipe_scale;
-    
-    // 首先检查X轴是否重叠
-    let (width_factor, _) = pipe.pipe_type.get_collision_bounds();
-    let pipe_width = 52.0 * pipe_scale * width_factor;
-    let pipe_left = pipe_center_x - pipe_width / 2.0;
-    let pipe_right = pipe_center_x + pipe_width / 2.0;
-    
-    // 如果X轴没有重叠，直接返回false
-    if bird_right <= pipe_left || bird_left >= pipe_right {
-        return false;
-    }
-    
-    // X轴重叠时，使用基于高度区间的碰撞检测
-    if pipe.pipe_type.use_precise_collision() {
-        // 使用多段精确碰撞检测
-        let segments = pipe.pipe_type.get_collision_segments();
-        
-        for (seg_offset_x, seg_offset_y, width_factor, height_factor) in segments {
-            // 计算每个碰撞段的实际位置和尺寸
-            let segment_width = 52.0 * pipe_scale * width_factor;
-            let segment_height = 320.0 * pipe_scale * height_factor;
-            
-            // 段的中心位置（基础偏移 + 段偏移）
-            // 修正：seg_offset应该直接乘以pipe_scale，而不是先乘以320.0
-            let segment_center_x = pipe_transform.translation.x + 
-                (base_offset_x + seg_offset_x * 52.0) * pipe_scale;
-            let segment_center_y = pipe_transform.translation.y + 
-                (base_offset_y + seg_offset_y * 160.0) * pipe_scale;
-            
-            // 段的边界
-            let segment_left = segment_center_x - segment_width / 2.0;
-            let segment_right = segment_center_x + segment_width / 2.0;
-            let segment_bottom = segment_center_y - segment_height / 2.0;
-            let segment_top = segment_center_y + segment_height / 2.0;
-            
-            // 检查与当前段的碰撞（基于高度区间）
-            if bird_right > segment_left && bird_left < segment_right {
-                // X轴重叠时，检查Y轴是否在障碍物的高度范围内
-                if bird_top > segment_bottom && bird_bottom < segment_top {
-                    return true; // 在障碍物高度范围内，发生碰撞
-                }
-            }
-        }
-        false // 不在任何障碍物段的高度范围内，可以通过
-    } else {
-        // 传统管道使用简单AABB碰撞检测
-        let (_, height_factor) = pipe.pipe_type.get_collision_bounds();
-        
-        // 计算管道的实际碰撞区域
-        let pipe_height = 320.0 * pipe_scale * height_factor;
-        
-        // 管道边界
-        let pipe_bottom = pipe_center_y - pipe_height / 2.0;
-        let pipe_top = pipe_center_y + pipe_height / 2.0;
-        
-        // 基于高度区间的碰撞检测
-        bird_top > pipe_bottom && bird_bottom < pipe_top
-    }
-}
\ No newline at end of file
@@ -0,0 +1,391 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::window::PrimaryWindow;
+use rand::Rng;
+use crate::character::{CharacterRegistry, ClipKind};
+use crate::collision::{AlphaMask, CollisionMasks};
+use crate::components::*;
+use crate::parallax::ParallaxMode;
+use crate::resources::*;
+
+// ===== 渲染系统 =====
+
+// 两个固定步长之间位移超过该距离时视为瞬移（如背景循环），不做插值
+const TELEPORT_DISTANCE: f32 = 200.0;
+
+// 视差背景覆盖的屏幕宽度
+const SCREEN_WIDTH: f32 = 800.0;
+
+// 循环层的元素移出左侧该位置后，回到右侧该位置之外
+const PARALLAX_RECYCLE_X: f32 = 600.0;
+
+// 两帧之间竖直速度增大超过该值视为拍打（重力只会让速度减小）
+const FLAP_VELOCITY_JUMP: f32 = 1.0;
+
+// 贴图缺少时碰撞遮罩使用的尺寸（经典小鸟和管道贴图的尺寸）
+const FALLBACK_BIRD_SIZE: UVec2 = UVec2::new(34, 24);
+const FALLBACK_PIPE_SIZE: UVec2 = UVec2::new(52, 320);
+
+// 准备阶段小鸟上下浮动的幅度（像素）和频率（弧度/秒）
+const READY_BOB_AMPLITUDE: f32 = 8.0;
+const READY_BOB_SPEED: f32 = 4.0;
+
+// 按描述生成视差背景各层（纯装饰，模拟核心不需要）
+pub fn setup_background(mut commands: Commands, assets: Res<GameAssets>) {
+    for (index, (layer, textures)) in assets.parallax_layers.iter().zip(&assets.parallax_textures).enumerate() {
+        if textures.is_empty() {
+            continue;
+        }
+        let y = (layer.y[0] + layer.y[1]) / 2.0;
+        for i in 0..layer.mode.count(SCREEN_WIDTH) {
+            // 平铺层从屏幕左边缘开始首尾相接，循环层按固定间隔排开
+            let x = match layer.mode {
+                ParallaxMode::Tile { width } => -SCREEN_WIDTH / 2.0 + width / 2.0 + i as f32 * width,
+                ParallaxMode::Recycle { spacing, .. } => -SCREEN_WIDTH / 2.0 + i as f32 * spacing,
+            };
+            let transform = Transform::from_translation(Vec3::new(x, y, layer.depth))
+                .with_scale(Vec3::splat(layer.scale));
+            commands.spawn((
+                Sprite::from_image(textures[i % textures.len()].clone()),
+                transform,
+                TransformInterpolation::new(transform),
+                Parallax { layer: index },
+            ));
+        }
+    }
+}
+
+// 视差背景随管道速度按比例滚动，与模拟在同一个固定步上推进
+// 平铺层移出左侧后接到最右边；循环层隔一段随机距离回到右侧，并随机换贴图和高度
+pub fn parallax_system(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    mut rng: ResMut<GameRng>,
+    mut query: Query<(&mut Transform, &mut Sprite, &Parallax)>,
+) {
+    for (mut transform, mut sprite, parallax) in query.iter_mut() {
+        let (Some(layer), Some(textures)) = (
+            assets.parallax_layers.get(parallax.layer),
+            assets.parallax_textures.get(parallax.layer),
+        ) else {
+            continue;
+        };
+        transform.translation.x -= layer.speed_factor * config.pipe_speed * time.delta_secs();
+
+        match layer.mode {
+            ParallaxMode::Tile { width } => {
+                if transform.translation.x + width / 2.0 < -SCREEN_WIDTH / 2.0 {
+                    transform.translation.x += width * layer.mode.count(SCREEN_WIDTH) as f32;
+                }
+            }
+            ParallaxMode::Recycle { gap, .. } => {
+                if transform.translation.x < -PARALLAX_RECYCLE_X {
+                    let scenery = rng.scenery();
+                    transform.translation.x = PARALLAX_RECYCLE_X + scenery.random_range(gap[0]..=gap[1]);
+                    transform.translation.y = scenery.random_range(layer.y[0]..=layer.y[1]);
+                    sprite.image = textures[scenery.random_range(0..textures.len())].clone();
+                }
+            }
+        }
+    }
+}
+
+// 为新生成的小鸟附加精灵和动画
+pub fn attach_bird_sprite(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    characters: Option<Res<CharacterRegistry>>,
+    bird_query: Query<(Entity, &Bird, &Transform), Added<Bird>>,
+) {
+    for (entity, bird, transform) in bird_query.iter() {
+        let mut bird_entity = commands.entity(entity);
+        bird_entity.insert((
+            assets.get_bird_sprite(&bird.character),
+            TransformInterpolation::new(*transform),
+        ));
+
+        if let Some(character) = characters.as_deref().and_then(|characters| characters.get(&bird.character)) {
+            bird_entity.insert(BirdAnimation::new(character.clips.clone()));
+        }
+    }
+}
+
+// 为地面附加横向平铺的精灵，宽度覆盖窗口和一个滚动循环周期，锚点在顶部
+pub fn attach_ground_sprite(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    ground_query: Query<(Entity, &Transform), Added<Ground>>,
+) {
+    for (entity, transform) in ground_query.iter() {
+        commands.entity(entity).insert((
+            Sprite {
+                image: assets.ground_texture.clone(),
+                custom_size: Some(Vec2::new(800.0 + GROUND_SCROLL_WRAP * 2.0, GROUND_HEIGHT)),
+                image_mode: SpriteImageMode::Tiled {
+                    tile_x: true,
+                    tile_y: false,
+                    stretch_value: 1.0,
+                },
+                anchor: Anchor::TopCenter,
+                ..default()
+            },
+            TransformInterpolation::new(*transform),
+        ));
+    }
+}
+
+// 为新生成的管道附加精灵
+// 管道对本身没有精灵，需要可见性组件才能把可见性传给子实体
+pub fn attach_pipe_pair_visibility(
+    mut commands: Commands,
+    pair_query: Query<(Entity, &Transform), Added<PipePair>>,
+) {
+    for (entity, transform) in pair_query.iter() {
+        commands.entity(entity).insert((
+            Visibility::default(),
+            TransformInterpolation::new(*transform),
+        ));
+    }
+}
+
+pub fn attach_pipe_sprite(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    pipe_query: Query<(Entity, &Pipe, &Transform), Added<Pipe>>,
+) {
+    for (entity, pipe, transform) in pipe_query.iter() {
+        commands.entity(entity).insert((
+            Sprite::from_image(assets.get_pipe_texture(pipe.pipe_type)),
+            TransformInterpolation::new(*transform),
+        ));
+    }
+}
+
+// 固定步长循环开始前，把插值后的变换恢复为模拟的真实状态
+pub fn restore_interpolated_transforms(
+    mut query: Query<(&mut Transform, &TransformInterpolation)>,
+) {
+    for (mut transform, interpolation) in query.iter_mut() {
+        *transform = interpolation.current;
+    }
+}
+
+// 每个固定步长开始时记录上一步的变换
+pub fn record_previous_transforms(
+    mut query: Query<(&Transform, &mut TransformInterpolation)>,
+) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.previous = *transform;
+    }
+}
+
+// 每个固定步长结束时记录模拟后的变换
+pub fn record_current_transforms(
+    mut query: Query<(&Transform, &mut TransformInterpolation)>,
+) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = *transform;
+    }
+}
+
+// 根据固定步长的剩余进度在两次模拟结果之间插值
+pub fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &TransformInterpolation)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+
+    for (mut transform, interpolation) in query.iter_mut() {
+        let previous = interpolation.previous;
+        let current = interpolation.current;
+
+        if previous.translation.distance(current.translation) > TELEPORT_DISTANCE {
+            *transform = current;
+            continue;
+        }
+
+        transform.translation = previous.translation.lerp(current.translation, alpha);
+        transform.rotation = previous.rotation.slerp(current.rotation, alpha);
+    }
+}
+
+// 准备阶段显示“按空格开始”提示
+pub fn setup_ready_prompt(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        Sprite::from_image(assets.space_to_start_texture.clone()),
+        Transform::from_translation(Vec3::new(0.0, 120.0, 5.0)).with_scale(Vec3::splat(0.5)),
+        ReadyPrompt,
+    ));
+}
+
+pub fn cleanup_ready_prompt(mut commands: Commands, query: Query<Entity, With<ReadyPrompt>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// 撞击闪光的持续时间和初始透明度
+const DEATH_FLASH_SECS: f32 = 0.3;
+const DEATH_FLASH_ALPHA: f32 = 0.8;
+
+// 撞击时全屏闪白
+pub fn setup_death_flash(mut commands: Commands) {
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(1.0, 1.0, 1.0, DEATH_FLASH_ALPHA),
+            custom_size: Some(Vec2::new(800.0, 600.0)),
+            ..default()
+        },
+        Transform::from_translation(Vec3::new(0.0, 0.0, 7.0)),
+        DeathFlash(Timer::from_seconds(DEATH_FLASH_SECS, TimerMode::Once)),
+    ));
+}
+
+pub fn death_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Sprite, &mut DeathFlash)>,
+) {
+    for (entity, mut sprite, mut flash) in query.iter_mut() {
+        flash.0.tick(time.delta());
+        sprite.color.set_alpha(DEATH_FLASH_ALPHA * (1.0 - flash.0.fraction()));
+        if flash.0.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// 小鸟动画：拍打（速度突然增大）后播放 flap，俯冲时播放 fall，撞击后播放 death，其余时间播放 idle
+// 幽灵与真实小鸟使用同样的规则，只是不会撞击
+pub fn bird_animation_system(
+    time: Res<Time>,
+    clock: Res<SimulationClock>,
+    images: Res<Assets<Image>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    mut query: Query<(&mut BirdAnimation, &mut Sprite, &Velocity, &CharacterStats, Has<Bird>)>,
+) {
+    for (mut animation, mut sprite, velocity, stats, is_bird) in query.iter_mut() {
+        let flapped = velocity.y > animation.last_velocity + FLAP_VELOCITY_JUMP;
+        animation.last_velocity = velocity.y;
+        
+        // flap 和 death 播放完之前不被 idle、fall 打断
+        let playing_once = matches!(animation.clip, ClipKind::Flap | ClipKind::Death)
+            && animation.current_clip().is_some_and(|clip| !clip.is_finished(animation.elapsed));
+        let clip = if is_bird && clock.crashed {
+            ClipKind::Death
+        } else if flapped {
+            ClipKind::Flap
+        } else if playing_once {
+            animation.clip
+        } else if velocity.y < -stats.tilt_velocity {
+            ClipKind::Fall
+        } else {
+            ClipKind::Idle
+        };
+        if flapped || clip != animation.clip {
+            animation.play(clip);
+        } else {
+            animation.elapsed += time.delta_secs();
+        }
+        
+        let Some((index, stretch)) = animation.current_clip().map(|clip| clip.frame_at(animation.elapsed)) else {
+            continue;
+        };
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = index;
+        }
+        
+        // 拉伸时保持面积不变
+        let frame_size = match &sprite.texture_atlas {
+            Some(atlas) => layouts
+                .get(&atlas.layout)
+                .and_then(|layout| layout.textures.get(atlas.index))
+                .map(|rect| rect.size().as_vec2()),
+            None => images.get(&sprite.image).map(|image| image.size_f32()),
+        };
+        sprite.custom_size = frame_size
+            .filter(|_| stretch != 1.0)
+            .map(|size| size * Vec2::new(1.0 / stretch, stretch));
+    }
+}
+
+// 准备阶段的悬停浮动，只影响显示
+// 在插值之后叠加偏移，下一次固定步长循环前会恢复为模拟的真实位置
+pub fn ready_bob_system(
+    time: Res<Time>,
+    mut query: Query<&mut Transform, (With<TransformInterpolation>, Or<(With<Bird>, With<GhostBird>)>)>,
+) {
+    let offset = (time.elapsed_secs() * READY_BOB_SPEED).sin() * READY_BOB_AMPLITUDE;
+    for mut transform in query.iter_mut() {
+        transform.translation.y += offset;
+    }
+}
+
+// 图片加载完成后构建碰撞遮罩
+// 遮罩使用角色的静态纹理，与动画帧无关，保证模拟结果不受渲染影响
+pub fn build_collision_masks(
+    mut commands: Commands,
+    assets: Option<Res<GameAssets>>,
+    characters: Option<Res<CharacterRegistry>>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
+) {
+    let (Some(assets), Some(characters)) = (assets, characters) else {
+        return;
+    };
+
+    let mut masks = CollisionMasks::default();
+    for character in characters.iter() {
+        let handle = assets.get_bird_texture(&character.id);
+        let Some(mask) = texture_mask(&asset_server, &images, &handle, &character.texture, FALLBACK_BIRD_SIZE) else {
+            return;
+        };
+        masks.birds.insert(character.id.clone(), mask);
+    }
+    for pipe_type in PipeType::all_types() {
+        let handle = assets.get_pipe_texture(pipe_type);
+        let Some(mask) = texture_mask(&asset_server, &images, &handle, pipe_type.get_texture_path(), FALLBACK_PIPE_SIZE) else {
+            return;
+        };
+        masks.pipes.insert(pipe_type, mask);
+    }
+
+    commands.insert_resource(masks);
+}
+
+// 单张贴图的碰撞遮罩，贴图还在加载时返回 None
+// 贴图缺少或无法解码时退回到整张不透明的遮罩（即按图片尺寸做 AABB 检测），
+// 缺少的贴图没有尺寸，使用经典贴图的尺寸
+fn texture_mask(
+    asset_server: &AssetServer,
+    images: &Assets<Image>,
+    handle: &Handle<Image>,
+    path: &str,
+    fallback_size: UVec2,
+) -> Option<AlphaMask> {
+    if let Some(image) = images.get(handle) {
+        return Some(AlphaMask::from_image(image).unwrap_or_else(|| {
+            eprintln!("碰撞遮罩错误 {}: 无法读取贴图像素，按图片尺寸做矩形检测", path);
+            AlphaMask::solid(image.size())
+        }));
+    }
+    match asset_server.get_load_state(handle) {
+        Some(LoadState::Loading | LoadState::NotLoaded) => None,
+        _ => {
+            eprintln!("碰撞遮罩错误 {}: 贴图加载失败，按 {}x{} 的矩形检测", path, fallback_size.x, fallback_size.y);
+            Some(AlphaMask::solid(fallback_size))
+        }
+    }
+}
+
+// 窗口尺寸变化时更新可活动区域
+pub fn sync_playfield_bounds(
+    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut bounds: ResMut<PlayfieldBounds>,
+) {
+    if let Ok(window) = window_query.single() {
+        *bounds = PlayfieldBounds::from_window_size(window.size());
+    }
+}
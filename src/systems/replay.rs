@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use crate::character::CharacterRegistry;
+use crate::collision::CollisionMasks;
+use crate::events::FlapEvent;
+use crate::replay::*;
+use crate::resources::*;
+use crate::states::*;
+
+// ===== 回放系统 =====
+
+// 资源加载完成后开始播放启动参数指定的回放
+// 状态切换早于 Startup，直接在启动时进入回放状态会在资源插入之前运行 OnEnter 系统
+pub fn start_pending_replay(
+    mut commands: Commands,
+    pending: Option<Res<PendingReplay>>,
+    assets: Option<Res<GameAssets>>,
+    characters: Option<Res<CharacterRegistry>>,
+    masks: Option<Res<CollisionMasks>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let (Some(pending), Some(_), Some(_), Some(_)) = (pending, assets, characters, masks) else {
+        return;
+    };
+
+    commands.insert_resource(ReplayPlayback::new(pending.0.clone()));
+    commands.remove_resource::<PendingReplay>();
+    next_state.set(GameState::Replay);
+}
+
+// 进入回放前应用录制时的配置和固定步长
+// 录制后角色描述被修改或删除时回放无法复现，拒绝播放并返回菜单
+pub fn setup_replay(
+    playback: Option<ResMut<ReplayPlayback>>,
+    characters: Option<Res<CharacterRegistry>>,
+    mut config: ResMut<GameConfig>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut playback) = playback else {
+        eprintln!("没有可播放的回放");
+        next_state.set(GameState::Menu);
+        return;
+    };
+    let checked = match characters {
+        Some(characters) => playback.replay.check_character(&characters),
+        None => Err("角色描述还没有加载完成".to_string()),
+    };
+    if let Err(e) = checked {
+        eprintln!("无法播放回放: {}", e);
+        next_state.set(GameState::Menu);
+        return;
+    }
+
+    playback.next_flap = 0;
+    playback.previous_config = Some(ReplayConfig::from_config(&config));
+    playback.previous_timestep = Some(fixed_time.timestep());
+
+    playback.replay.config.apply_to(&mut config);
+    fixed_time.set_timestep(playback.replay.timestep);
+}
+
+// 回放结束后恢复原来的配置和固定步长
+pub fn cleanup_replay(
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut config: ResMut<GameConfig>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+
+    if let Some(previous_config) = playback.previous_config.take() {
+        previous_config.apply_to(&mut config);
+    }
+    if let Some(previous_timestep) = playback.previous_timestep.take() {
+        fixed_time.set_timestep(previous_timestep);
+    }
+}
+
+// 在录制的固定步上重新发出拍打事件
+pub fn replay_input_system(
+    clock: Res<SimulationClock>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut flap_events: EventWriter<FlapEvent>,
+) {
+    let playback = &mut *playback;
+    if take_flap(&playback.replay.flaps, &mut playback.next_flap, clock.tick) {
+        flap_events.write(FlapEvent);
+    }
+}
+
+// 保存本局回放，普通对局超过个人最佳时同时保存为最佳回放
+pub fn save_replay(
+    recorder: Option<Res<ReplayRecorder>>,
+    playback: Option<Res<ReplayPlayback>>,
+    run_mode: Res<RunMode>,
+    game_data: Res<GameData>,
+    clock: Res<SimulationClock>,
+    save_manager: Res<SaveManager>,
+) {
+    // 回放本身不再保存
+    if playback.is_some() {
+        return;
+    }
+    let Some(recorder) = recorder else {
+        return;
+    };
+
+    let mut replay = recorder.0.clone();
+    replay.score = game_data.score;
+    replay.ticks = clock.tick;
+
+    if let Err(e) = replay.save(&save_manager.replay_path(LAST_REPLAY)) {
+        eprintln!("保存回放失败: {}", e);
+    }
+
+    // 每日挑战使用统一的规则和日期种子，不作为普通对局的最佳回放和幽灵
+    if *run_mode != RunMode::Normal {
+        return;
+    }
+
+    let best_path = save_manager.replay_path(BEST_REPLAY);
+    let is_best = Replay::load(&best_path).map_or(true, |best| replay.score > best.score);
+    if is_best {
+        if let Err(e) = replay.save(&best_path) {
+            eprintln!("保存最佳回放失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::components::*;
+use crate::resources::*;
+use crate::settings::Settings;
+use crate::states::*;
+use crate::theme::{local_day_fraction, SkyCycle};
+
+// ===== 天空和天气系统 =====
+
+// 天气粒子生成的高度，以及横向生成范围（风向左吹，右侧多留一些）
+const WEATHER_SPAWN_Y: f32 = 320.0;
+const WEATHER_SPAWN_X: [f32; 2] = [-420.0, 620.0];
+
+// 天气粒子在屏幕上的层级：在场景之前，在界面蒙版之后
+const WEATHER_Z: f32 = 4.0;
+
+// 按天空主题混合清屏颜色、背景层、地面和障碍物的颜色
+pub fn sky_theme_system(
+    state: Res<State<GameState>>,
+    clock: Res<SimulationClock>,
+    fixed_time: Res<Time<Fixed>>,
+    settings: Res<Settings>,
+    assets: Res<GameAssets>,
+    sky_cycle: Res<SkyCycle>,
+    mut clear_color: ResMut<ClearColor>,
+    mut parallax_query: Query<(&mut Sprite, &Parallax)>,
+    mut scene_query: Query<&mut Sprite, (Or<(With<Pipe>, With<Ground>)>, Without<Parallax>)>,
+) {
+    // 菜单和排行榜显示一局开始时的样子
+    let elapsed = match state.get() {
+        GameState::Menu | GameState::Leaderboard | GameState::Settings => 0.0,
+        _ => clock.tick as f32 * fixed_time.timestep().as_secs_f32(),
+    };
+    let look = sky_cycle.look(settings.sky_theme, elapsed, local_day_fraction());
+    let [r, g, b] = look.tint;
+
+    clear_color.0 = Color::srgb(look.sky[0], look.sky[1], look.sky[2]);
+    for (mut sprite, parallax) in parallax_query.iter_mut() {
+        let Some(layer) = assets.parallax_layers.get(parallax.layer) else {
+            continue;
+        };
+        let alpha = look.layer_alpha(&layer.name);
+        sprite.color = if layer.tinted { Color::srgba(r, g, b, alpha) } else { Color::srgba(1.0, 1.0, 1.0, alpha) };
+    }
+    for mut sprite in scene_query.iter_mut() {
+        sprite.color = Color::srgb(r, g, b);
+    }
+}
+
+// 天气粒子：在屏幕上方随机位置生成，落到地面后移除
+// 纯装饰，使用线程随机数，不影响回放
+pub fn weather_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    bounds: Res<PlayfieldBounds>,
+    mut spawn_budget: Local<f32>,
+    mut query: Query<(Entity, &mut Transform, &WeatherParticle)>,
+) {
+    for (entity, mut transform, particle) in query.iter_mut() {
+        transform.translation += (particle.velocity * time.delta_secs()).extend(0.0);
+        if transform.translation.y < bounds.floor || transform.translation.x < WEATHER_SPAWN_X[0] - 100.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let Some(particles) = settings.weather.particles() else {
+        *spawn_budget = 0.0;
+        return;
+    };
+    *spawn_budget += particles.rate * time.delta_secs();
+    let mut rng = rand::rng();
+    while *spawn_budget >= 1.0 {
+        *spawn_budget -= 1.0;
+        let velocity = Vec2::new(
+            particles.velocity[0] + rng.random_range(-particles.jitter..=particles.jitter),
+            particles.velocity[1],
+        );
+        let [r, g, b, a] = particles.color;
+        commands.spawn((
+            Sprite {
+                color: Color::srgba(r, g, b, a),
+                custom_size: Some(Vec2::from(particles.size)),
+                ..default()
+            },
+            // 雨丝沿下落方向倾斜
+            Transform::from_translation(Vec3::new(
+                rng.random_range(WEATHER_SPAWN_X[0]..WEATHER_SPAWN_X[1]),
+                WEATHER_SPAWN_Y,
+                WEATHER_Z,
+            ))
+            .with_rotation(Quat::from_rotation_z(velocity.x.atan2(-velocity.y))),
+            WeatherParticle { velocity },
+        ));
+    }
+}
+
+pub fn cleanup_weather(mut commands: Commands, query: Query<Entity, With<WeatherParticle>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
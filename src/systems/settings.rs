@@ -0,0 +1,212 @@
+use bevy::audio::{GlobalVolume, Volume};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::components::*;
+use crate::difficulty::DifficultyCurves;
+use crate::locale::Locale;
+use crate::resources::*;
+use crate::settings::{Settings, SettingsItem};
+use crate::states::*;
+use crate::audio::AudioEvent;
+
+// ===== 设置界面系统 =====
+
+// 设置行的布局：第一行的位置、行高和可点击的宽度
+const SETTINGS_ROW_TOP: f32 = 190.0;
+const SETTINGS_ROW_HEIGHT: f32 = 36.0;
+const SETTINGS_ROW_WIDTH: f32 = 560.0;
+
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.8, 0.0);
+const UNSELECTED_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+pub fn setup_settings(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+) {
+    spawn_settings_screen(&mut commands, &assets, &settings, &locale, 0);
+}
+
+// 生成整个设置界面，切换语言后需要全部重建
+fn spawn_settings_screen(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    settings: &Settings,
+    locale: &Locale,
+    selected: usize,
+) {
+    commands.spawn((
+        Text2d::new(locale.get("settings.title")),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 44.0,
+            ..default()
+        },
+        TextColor(SELECTED_COLOR),
+        Transform::from_translation(Vec3::new(0.0, 250.0, 1.0)),
+        SettingsText,
+    ));
+
+    for (index, item) in SettingsItem::ALL.iter().enumerate() {
+        commands.spawn((
+            Text2d::new(settings_row_label(locale, settings, *item)),
+            TextFont {
+                font: assets.font(locale.language()),
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(if index == selected { SELECTED_COLOR } else { UNSELECTED_COLOR }),
+            Transform::from_translation(Vec3::new(0.0, settings_row_y(index), 1.0)),
+            SettingsRow(index),
+            SettingsText,
+        ));
+    }
+
+    // 操作提示
+    commands.spawn((
+        Text2d::new(locale.get("settings.help")),
+        TextFont {
+            font: assets.font(locale.language()),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        Transform::from_translation(Vec3::new(0.0, -250.0, 1.0)),
+        SettingsText,
+    ));
+}
+
+fn settings_row_y(index: usize) -> f32 {
+    SETTINGS_ROW_TOP - index as f32 * SETTINGS_ROW_HEIGHT
+}
+
+// 一行设置的文字：名称和当前值
+fn settings_row_label(locale: &Locale, settings: &Settings, item: SettingsItem) -> String {
+    let value = match item {
+        SettingsItem::Volume => format!("{}%", settings.volume),
+        SettingsItem::WindowSize => locale.get(settings.window_size.locale_key()).to_string(),
+        SettingsItem::Language => locale.get("language.name").to_string(),
+        SettingsItem::FlapKey => locale.get(settings.flap_key.locale_key()).to_string(),
+        SettingsItem::Difficulty => locale.get(settings.difficulty.locale_key()).to_string(),
+        SettingsItem::Ghost => locale.get(if settings.ghost_enabled { "common.on" } else { "common.off" }).to_string(),
+        SettingsItem::PipeTheme => locale.get(settings.pipe_theme.locale_key()).to_string(),
+        SettingsItem::Ceiling => locale.get(settings.ceiling.locale_key()).to_string(),
+        SettingsItem::SkyTheme => locale.get(settings.sky_theme.locale_key()).to_string(),
+        SettingsItem::Weather => locale.get(settings.weather.locale_key()).to_string(),
+        SettingsItem::Back => return locale.get(item.locale_key()).to_string(),
+    };
+    locale.format("settings.row", &[("name", &locale.get(item.locale_key())), ("value", &value)])
+}
+
+// 键盘：↑ ↓ 选择，← → 或回车调整，ESC 返回
+// 鼠标：悬停选择，左键调整
+pub fn settings_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    assets: Res<GameAssets>,
+    mut settings: ResMut<Settings>,
+    mut locale: ResMut<Locale>,
+    mut config: ResMut<GameConfig>,
+    curves: Res<DifficultyCurves>,
+    save_manager: Res<SaveManager>,
+    mut selected: Local<usize>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut audio_events: EventWriter<AudioEvent>,
+    mut row_query: Query<(&SettingsRow, &mut TextColor)>,
+    screen_query: Query<Entity, With<SettingsText>>,
+) {
+    let count = SettingsItem::ALL.len();
+    let mut activate = None;
+
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        *selected = (*selected + count - 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        *selected = (*selected + 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        activate = Some(false);
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight)
+        || keyboard_input.just_pressed(KeyCode::Enter)
+        || keyboard_input.just_pressed(KeyCode::Space)
+    {
+        activate = Some(true);
+    }
+
+    // 鼠标悬停在某一行上时选中该行，点击时调整
+    let cursor = window_query
+        .single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .zip(camera_query.single().ok())
+        .and_then(|(position, (camera, camera_transform))| camera.viewport_to_world_2d(camera_transform, position).ok());
+    if let Some(cursor) = cursor {
+        let hovered = (0..count).find(|&index| {
+            cursor.x.abs() < SETTINGS_ROW_WIDTH / 2.0 && (cursor.y - settings_row_y(index)).abs() < SETTINGS_ROW_HEIGHT / 2.0
+        });
+        if let Some(index) = hovered {
+            *selected = index;
+            if mouse_input.just_pressed(MouseButton::Left) {
+                activate = Some(true);
+            }
+        }
+    }
+
+    let item = SettingsItem::ALL[*selected % count];
+    if keyboard_input.just_pressed(KeyCode::Escape) || (activate.is_some() && item == SettingsItem::Back) {
+        audio_events.write(AudioEvent::Swoosh);
+        next_state.set(GameState::Menu);
+        return;
+    }
+
+    if let Some(forward) = activate {
+        let mut updated = settings.clone();
+        // 没有可用字体的语言（如系统没有中文字体时的中文）不能切换过去
+        if item.adjust(&mut updated, forward) && assets.fonts.contains_key(&updated.language) {
+            *settings = updated;
+            if let Err(e) = save_manager.save_settings(&settings) {
+                eprintln!("保存设置失败: {}", e);
+            }
+            // 障碍主题、顶部边界和难度立即应用到下一局
+            settings.apply_to(&mut config, &curves);
+            if locale.language() != settings.language {
+                *locale = Locale::new(settings.language);
+            }
+            audio_events.write(AudioEvent::Swoosh);
+
+            for entity in screen_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            spawn_settings_screen(&mut commands, &assets, &settings, &locale, *selected);
+            return;
+        }
+    }
+
+    for (row, mut color) in row_query.iter_mut() {
+        color.0 = if row.0 == *selected { SELECTED_COLOR } else { UNSELECTED_COLOR };
+    }
+}
+
+// 设置改变后应用到音量和窗口大小
+pub fn apply_settings(
+    settings: Res<Settings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    global_volume.volume = Volume::Linear(settings.linear_volume());
+
+    let scale_factor_override = settings.window_size.scale_factor_override();
+    for mut window in window_query.iter_mut() {
+        if window.resolution.scale_factor_override() != scale_factor_override {
+            window.resolution.set_scale_factor_override(scale_factor_override);
+        }
+    }
+}
+
+pub fn cleanup_settings(mut commands: Commands, query: Query<Entity, With<SettingsText>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,8 +1,14 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 use bevy::winit::WinitWindows;
+use bevy::asset::LoadedFolder;
+use crate::asset_check::{asset_dir, check_assets, FontChain};
+use crate::character::{CharacterManifest, CharacterRegistry, CHARACTER_DIR};
 use crate::components::*;
+use crate::difficulty::{DifficultyCurve, DifficultyCurves, DifficultyPreset};
+use crate::locale::{Language, Locale};
+use crate::parallax::builtin_layers;
 use crate::resources::*;
+use std::collections::HashMap;
 
 // ===== 设置和清理系统 =====
 
@@ -10,22 +16,27 @@ pub fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
-pub fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // 加载所有小鸟纹理
-    let bird_textures = BirdCharacter::all_characters()
-        .iter()
-        .map(|character| asset_server.load(character.get_texture_path()))
-        .collect();
+// 启动时检查所有引用的资源，缺少或损坏的文件逐个报告
+pub fn validate_assets() {
+    let report = check_assets(&asset_dir());
+    for problem in &report.problems {
+        eprintln!("资源错误 {}", problem);
+    }
+}
+
+pub fn load_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut fonts: ResMut<Assets<Font>>,
+    mut locale: ResMut<Locale>,
+) {
+    // 加载所有角色描述，小鸟纹理在描述加载完成后由 build_character_registry 加载
+    let character_folder = asset_server.load_folder(CHARACTER_DIR);
     
-    // 加载所有小鸟的动画帧
-    let bird_animation_frames = BirdCharacter::all_characters()
-        .iter()
-        .map(|character| {
-            character.get_animation_frames()
-                .iter()
-                .map(|frame_path| asset_server.load(*frame_path))
-                .collect()
-        })
+    // 加载所有难度预设的描述，由 build_difficulty_curves 在加载完成后整理
+    let difficulty_curves = DifficultyPreset::ALL
+        .into_iter()
+        .map(|preset| (preset, asset_server.load(preset.path())))
         .collect();
     
     // 加载所有管道纹理
@@ -36,29 +47,131 @@ pub fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
 
     // 加载数字纹理 (0-9)
     let number_textures = (0..10)
-        .map(|i| asset_server.load(format!("numbers/{}.png", i)))
+        .map(|i| asset_server.load(number_texture_path(i)))
         .collect();
 
-    
+    // 加载视差背景各层的贴图池
+    let parallax_layers = builtin_layers();
+    let parallax_textures = parallax_layers
+        .iter()
+        .map(|layer| layer.textures.iter().map(|path| asset_server.load(path)).collect())
+        .collect();
+
+    // 字体链直接从磁盘读取，启动时就能确定每个字体覆盖哪些文字
+    let chain = FontChain::load(&asset_dir());
+    for problem in &chain.problems {
+        eprintln!("字体错误 {}", problem);
+    }
+    for warning in &chain.warnings {
+        eprintln!("字体警告 {}", warning);
+    }
+    // 每种语言使用字体链中第一个能显示全部界面文字的字体
+    let language_fonts: Vec<(Language, usize)> = Language::ALL
+        .into_iter()
+        .filter_map(|language| Some((language, chain.font_for(language)?)))
+        .collect();
+    let loaded: Vec<Handle<Font>> = chain.fonts.into_iter().map(|file| fonts.add(file.font)).collect();
+    let font_handles: HashMap<Language, Handle<Font>> = language_fonts
+        .into_iter()
+        .map(|(language, index)| (language, loaded[index].clone()))
+        .collect();
+    // 当前语言没有可用字体时（如系统没有中文字体）改用英文界面，避免文字显示为方块
+    if !font_handles.contains_key(&locale.language()) && font_handles.contains_key(&Language::En) {
+        eprintln!("字体警告 没有可以显示 {} 界面的字体，界面使用英文", locale.code());
+        *locale = Locale::new(Language::En);
+    }
 
     commands.insert_resource(GameAssets {
-        bird_textures,
-        bird_animation_frames,
-        pipe_texture: pipe_textures[0].clone(),
-        ground_texture: asset_server.load("mountain.png"), // 暂时使用mountain.png替代
-        cloud_texture: asset_server.load("cloud_1.png"),
-        mountain_texture: asset_server.load("mountain.png"),
-        font: asset_server.load("fonts/NotoSansSC-Regular.ttf"),
+        character_folder,
+        difficulty_curves,
+        bird_textures: HashMap::new(),
+        bird_sheets: HashMap::new(),
+        pipe_textures,
+        ground_texture: asset_server.load(GROUND_TEXTURE),
+        parallax_layers,
+        parallax_textures,
+        space_to_start_texture: asset_server.load(SPACE_TO_START_TEXTURE),
+        fonts: font_handles,
         number_textures,
     });
 }
 
+// 角色描述全部加载完成后建立角色列表，并加载各角色的纹理和精灵表
+// 格式错误的描述由 AssetServer 报告，跳过后继续使用其余角色
+pub fn build_character_registry(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    assets: Option<ResMut<GameAssets>>,
+    folders: Res<Assets<LoadedFolder>>,
+    manifests: Res<Assets<CharacterManifest>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut game_data: ResMut<GameData>,
+) {
+    let Some(mut assets) = assets else {
+        return;
+    };
+    let Some(folder) = folders.get(&assets.character_folder) else {
+        return;
+    };
+    let handles: Vec<Handle<CharacterManifest>> = folder
+        .handles
+        .iter()
+        .filter_map(|handle| handle.clone().try_typed().ok())
+        .collect();
+    if handles.iter().any(|handle| asset_server.load_state(handle).is_loading()) {
+        return;
+    }
+    
+    let characters = CharacterRegistry::new(handles.iter().filter_map(|handle| manifests.get(handle)).cloned().collect());
+    if characters.iter().next().is_none() {
+        eprintln!("没有找到任何角色描述: assets/{}", CHARACTER_DIR);
+    }
+    for character in characters.iter() {
+        assets.bird_textures.insert(character.id.clone(), asset_server.load(&character.texture));
+        if let Some(sheet) = &character.sheet {
+            let sheet_handles = (asset_server.load(&sheet.texture), layouts.add(sheet.layout()));
+            assets.bird_sheets.insert(character.id.clone(), sheet_handles);
+        }
+    }
+    
+    // 存档中的角色可能已经被删除
+    game_data.selected_character = characters.resolve(&game_data.selected_character);
+    commands.insert_resource(characters);
+}
+
+// 难度描述全部加载完成后建立各预设的曲线
+// 缺少或格式错误的描述由 AssetServer 报告，该预设使用经典难度
+pub fn build_difficulty_curves(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    assets: Option<Res<GameAssets>>,
+    curves: Res<Assets<DifficultyCurve>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if assets.difficulty_curves.values().any(|handle| asset_server.load_state(handle).is_loading()) {
+        return;
+    }
+    
+    let mut loaded = HashMap::new();
+    for (preset, handle) in &assets.difficulty_curves {
+        match curves.get(handle) {
+            Some(curve) => {
+                loaded.insert(*preset, curve.clone());
+            }
+            None => eprintln!("难度描述错误 {}: 无法加载，使用经典难度", preset.path()),
+        }
+    }
+    commands.insert_resource(DifficultyCurves::new(loaded));
+}
+
 // 设置窗口图标的系统
 pub fn set_window_icon(
     windows: NonSend<WinitWindows>,
 ) {
     // 使用image库直接加载图标文件
-    if let Ok(image) = image::open("assets/icon.png") {
+    if let Ok(image) = image::open(asset_dir().join(WINDOW_ICON)) {
         let image = image.into_rgba8();
         let (width, height) = image.dimensions();
         let rgba = image.into_raw();
@@ -79,41 +192,53 @@ pub fn cleanup_menu(mut commands: Commands, query: Query<Entity, With<MenuText>>
     }
 }
 
+// 清理模拟核心生成的实体：小鸟、管道和地面
 pub fn cleanup_game(
     mut commands: Commands,
     bird_query: Query<Entity, With<Bird>>,
-    pipe_query: Query<Entity, With<Pipe>>,
-    score_query: Query<Entity, With<ScoreDigit>>,
-    scrolling_query: Query<Entity, With<Scrolling>>,
+    pipe_query: Query<Entity, With<PipePair>>,
+    ground_query: Query<Entity, With<Ground>>,
 ) {
     // 清理小鸟
     for entity in bird_query.iter() {
         commands.entity(entity).despawn();
     }
     
-    // 清理管道
+    // 清理管道（连同子实体一起）
     for entity in pipe_query.iter() {
         commands.entity(entity).despawn();
     }
     
+    // 清理地面
+    for entity in ground_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// 清理客户端生成的场景：分数显示和视差背景
+pub fn cleanup_scene(
+    mut commands: Commands,
+    score_query: Query<Entity, With<ScoreDigit>>,
+    parallax_query: Query<Entity, With<Parallax>>,
+) {
     // 清理分数显示
     for entity in score_query.iter() {
         commands.entity(entity).despawn();
     }
     
-    // 清理滚动背景元素
-    for entity in scrolling_query.iter() {
+    // 清理视差背景
+    for entity in parallax_query.iter() {
         commands.entity(entity).despawn();
     }
 }
 
-pub fn cleanup_pipes(mut commands: Commands, query: Query<Entity, With<Pipe>>) {
+pub fn cleanup_pipes(mut commands: Commands, query: Query<Entity, With<PipePair>>) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
 }
 
-pub fn cleanup_background(mut commands: Commands, query: Query<Entity, With<Scrolling>>) {
+pub fn cleanup_background(mut commands: Commands, query: Query<Entity, With<Parallax>>) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
@@ -1,15 +1,26 @@
 use bevy::prelude::*;
+use crate::character::CharacterRegistry;
 use crate::components::*;
+use crate::locale::Locale;
 use crate::resources::*;
+use crate::replay::ReplayPlayback;
 use crate::states::*;
 use crate::audio::AudioEvent;
 
 // ===== UI和显示系统 =====
 
+// 结算面板滑入的距离和时长
+const GAME_OVER_SLIDE_DISTANCE: f32 = 600.0;
+const GAME_OVER_SLIDE_SECS: f32 = 0.4;
+
 pub fn setup_game_over(
     mut commands: Commands,
     assets: Res<GameAssets>,
     game_data: Res<GameData>,
+    locale: Res<Locale>,
+    rng: Res<GameRng>,
+    run_mode: Res<RunMode>,
+    playback: Option<Res<ReplayPlayback>>,
 ) {
     // 添加半透明黑色蒙版背景
     commands.spawn((
@@ -18,73 +29,113 @@ pub fn setup_game_over(
             custom_size: Some(Vec2::new(800.0, 600.0)), // 覆盖整个屏幕
             ..default()
         },
-        Transform::from_translation(Vec3::new(0.0, 0.0, 0.5)), // 在背景之上，文字之下
+        Transform::from_translation(Vec3::new(0.0, 0.0, 5.0)), // 在保留的撞击现场之上，文字之下
         GameOverText, // 使用相同的组件标记，方便清理
     ));
 
-    // 游戏结束标题
-    commands.spawn((
-        Text2d::new("游戏结束"),
-        TextFont {
-            font: assets.font.clone(),
-            font_size: 48.0,
-            ..default()
-        },
-        TextColor(Color::srgb(1.0, 0.8, 0.2)), // 金黄色标题
-        Transform::from_translation(Vec3::new(0.0, 100.0, 1.0)),
-        GameOverText,
-    ));
+    // 结算面板从屏幕下方滑入
+    let title = locale.get(if playback.is_some() { "game_over.replay_title" } else { "game_over.title" });
+    commands
+        .spawn((
+            Transform::from_translation(Vec3::new(0.0, -GAME_OVER_SLIDE_DISTANCE, 6.0)),
+            Visibility::default(),
+            SlideIn {
+                offset: GAME_OVER_SLIDE_DISTANCE,
+                timer: Timer::from_seconds(GAME_OVER_SLIDE_SECS, TimerMode::Once),
+            },
+            GameOverText,
+        ))
+        .with_children(|panel| {
+            // 游戏结束标题
+            panel.spawn((
+                Text2d::new(title),
+                TextFont {
+                    font: assets.font(locale.language()),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.8, 0.2)), // 金黄色标题
+                Transform::from_translation(Vec3::new(0.0, 100.0, 0.0)),
+            ));
 
-    // 分数显示
-    commands.spawn((
-        Text2d::new(format!("本次分数: {}", game_data.score)),
-        TextFont {
-            font: assets.font.clone(),
-            font_size: 36.0,
-            ..default()
-        },
-        TextColor(Color::srgb(0.9, 0.9, 1.0)), // 淡蓝色
-        Transform::from_translation(Vec3::new(0.0, 30.0, 1.0)),
-        GameOverText,
-    ));
+            // 分数显示
+            panel.spawn((
+                Text2d::new(locale.format("game_over.score", &[("score", &game_data.score)])),
+                TextFont {
+                    font: assets.font(locale.language()),
+                    font_size: 36.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 1.0)), // 淡蓝色
+                Transform::from_translation(Vec3::new(0.0, 30.0, 0.0)),
+            ));
 
-    // 最高分显示
-    commands.spawn((
-        Text2d::new(format!("最高分: {}", game_data.high_score)),
-        TextFont {
-            font: assets.font.clone(),
-            font_size: 32.0,
-            ..default()
-        },
-        TextColor(Color::srgb(1.0, 0.7, 0.7)), // 淡红色
-        Transform::from_translation(Vec3::new(0.0, -20.0, 1.0)),
-        GameOverText,
-    ));
+            // 最高分显示，回放时改为显示录制分数的校验结果，每日挑战显示日期
+            let summary = match (&playback, &*run_mode) {
+                (Some(playback), _) => {
+                    let verdict = if playback.replay.score == game_data.score { "game_over.verified" } else { "game_over.mismatch" };
+                    locale.format(
+                        "game_over.replay_score",
+                        &[("score", &playback.replay.score), ("verdict", &locale.get(verdict))],
+                    )
+                }
+                (None, RunMode::Daily { date }) => locale.format("game_over.daily", &[("date", date)]),
+                (None, RunMode::Normal) => locale.format("game_over.high_score", &[("score", &game_data.high_score)]),
+            };
+            panel.spawn((
+                Text2d::new(summary),
+                TextFont {
+                    font: assets.font(locale.language()),
+                    font_size: 32.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.7, 0.7)), // 淡红色
+                Transform::from_translation(Vec3::new(0.0, -20.0, 0.0)),
+            ));
 
-    // 操作提示
-    commands.spawn((
-        Text2d::new("按 R 键重新开始"),
-        TextFont {
-            font: assets.font.clone(),
-            font_size: 28.0,
-            ..default()
-        },
-        TextColor(Color::srgb(0.8, 1.0, 0.8)), // 淡绿色
-        Transform::from_translation(Vec3::new(0.0, -80.0, 1.0)),
-        GameOverText,
-    ));
+            // 随机种子显示，便于复现本局
+            panel.spawn((
+                Text2d::new(locale.format("game_over.seed", &[("seed", &rng.seed())])),
+                TextFont {
+                    font: assets.font(locale.language()),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)), // 灰色
+                Transform::from_translation(Vec3::new(0.0, -50.0, 0.0)),
+            ));
 
-    commands.spawn((
-        Text2d::new("按 ESC 键返回菜单"),
-        TextFont {
-            font: assets.font.clone(),
-            font_size: 28.0,
-            ..default()
-        },
-        TextColor(Color::srgb(0.9, 0.9, 0.9)), // 浅灰色
-        Transform::from_translation(Vec3::new(0.0, -120.0, 1.0)),
-        GameOverText,
-    ));
+            // 操作提示
+            panel.spawn((
+                Text2d::new(locale.get("game_over.restart")),
+                TextFont {
+                    font: assets.font(locale.language()),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 1.0, 0.8)), // 淡绿色
+                Transform::from_translation(Vec3::new(0.0, -80.0, 0.0)),
+            ));
+
+            panel.spawn((
+                Text2d::new(locale.get("game_over.back")),
+                TextFont {
+                    font: assets.font(locale.language()),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)), // 浅灰色
+                Transform::from_translation(Vec3::new(0.0, -120.0, 0.0)),
+            ));
+        });
+}
+
+// 界面滑入，先快后慢
+pub fn slide_in_system(time: Res<Time>, mut query: Query<(&mut Transform, &mut SlideIn)>) {
+    for (mut transform, mut slide) in query.iter_mut() {
+        slide.timer.tick(time.delta());
+        transform.translation.y = -slide.offset * (1.0 - slide.timer.fraction()).powi(3);
+    }
 }
 
 pub fn number_score_display(
@@ -132,12 +183,21 @@ pub fn number_score_display(
 pub fn save_game_data(
     mut game_data: ResMut<GameData>,
     save_manager: Res<SaveManager>,
+    rng: Res<GameRng>,
+    run_mode: Res<RunMode>,
+    playback: Option<Res<ReplayPlayback>>,
 ) {
+    // 回放不计入排行榜和统计，每日挑战单独记录
+    if playback.is_some() || *run_mode != RunMode::Normal {
+        return;
+    }
+
     // 将当前分数添加到排行榜
     let updated_save_data = save_manager.add_score_to_leaderboard(
         game_data.save_data.clone(),
         game_data.score,
-        game_data.selected_character,
+        game_data.selected_character.clone(),
+        rng.seed(),
     );
     
     // 保存数据到文件
@@ -150,16 +210,42 @@ pub fn save_game_data(
     game_data.high_score = updated_save_data.high_score;
 }
 
+// 离开每日挑战时记录成绩（包括中途退出）
+pub fn save_daily_result(
+    mut game_data: ResMut<GameData>,
+    save_manager: Res<SaveManager>,
+    run_mode: Res<RunMode>,
+) {
+    let RunMode::Daily { date } = &*run_mode else {
+        return;
+    };
+    
+    let updated_save_data = save_manager.record_daily_result(
+        game_data.save_data.clone(),
+        date,
+        game_data.score,
+        daily_character(),
+    );
+    
+    if let Err(e) = save_manager.save_data(&updated_save_data) {
+        eprintln!("保存数据失败: {}", e);
+    }
+    
+    game_data.save_data = updated_save_data;
+}
+
 pub fn setup_leaderboard(
     mut commands: Commands,
     assets: Res<GameAssets>,
+    characters: Res<CharacterRegistry>,
     game_data: Res<GameData>,
+    locale: Res<Locale>,
 ) {
     // 主标题
     commands.spawn((
-        Text2d::new("🏆 排行榜 🏆"),
+        Text2d::new(locale.get("leaderboard.title")),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 48.0,
             ..default()
         },
@@ -168,11 +254,15 @@ pub fn setup_leaderboard(
         LeaderboardText,
     ));
     
-    // 左侧：排行榜标题
+    // 左侧：排行榜标题，只显示当前角色的成绩
+    let character = &game_data.selected_character;
+    let character_name = characters
+        .get(character)
+        .map_or(character.0.as_str(), |manifest| manifest.display_name(locale.code()));
     commands.spawn((
-        Text2d::new("前10名最高分"),
+        Text2d::new(locale.format("leaderboard.top", &[("name", &character_name)])),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 32.0,
             ..default()
         },
@@ -182,12 +272,12 @@ pub fn setup_leaderboard(
     ));
     
     // 左侧：排行榜条目
-    let leaderboard = &game_data.save_data.leaderboard;
+    let leaderboard: Vec<_> = game_data.save_data.leaderboard_for(character).take(10).collect();
     if leaderboard.is_empty() {
         commands.spawn((
-            Text2d::new("暂无记录\n\n开始游戏创建\n你的第一个记录吧！"),
+            Text2d::new(locale.get("leaderboard.empty")),
             TextFont {
-                font: assets.font.clone(),
+                font: assets.font(locale.language()),
                 font_size: 24.0,
                 ..default()
             },
@@ -196,7 +286,7 @@ pub fn setup_leaderboard(
             LeaderboardText,
         ));
     } else {
-        for (i, entry) in leaderboard.iter().enumerate().take(10) {
+        for (i, entry) in leaderboard.into_iter().enumerate() {
             let rank_color = match i {
                 0 => Color::srgb(1.0, 0.8, 0.0), // 金色
                 1 => Color::srgb(0.8, 0.8, 0.8), // 银色
@@ -218,25 +308,22 @@ pub fn setup_leaderboard(
                 .as_secs();
             let time_diff = now.saturating_sub(entry.timestamp);
             let formatted_time = if time_diff < 60 {
-                "刚刚".to_string()
+                locale.get("leaderboard.just_now").to_string()
             } else if time_diff < 3600 {
-                format!("{}分钟前", time_diff / 60)
+                locale.format("leaderboard.minutes_ago", &[("count", &(time_diff / 60))])
             } else if time_diff < 86400 {
-                format!("{}小时前", time_diff / 3600)
+                locale.format("leaderboard.hours_ago", &[("count", &(time_diff / 3600))])
             } else {
-                format!("{}天前", time_diff / 86400)
+                locale.format("leaderboard.days_ago", &[("count", &(time_diff / 86400))])
             };
             
             commands.spawn((
-                Text2d::new(format!(
-                    "{} {} - {} 分\n    {}",
-                    rank_symbol,
-                    entry.character.get_name(),
-                    entry.score,
-                    formatted_time
+                Text2d::new(locale.format(
+                    "leaderboard.entry",
+                    &[("rank", &rank_symbol), ("score", &entry.score), ("time", &formatted_time)],
                 )),
                 TextFont {
-                    font: assets.font.clone(),
+                    font: assets.font(locale.language()),
                     font_size: 20.0,
                     ..default()
                 },
@@ -248,20 +335,23 @@ pub fn setup_leaderboard(
     }
     
     // 右侧：统计信息
+    let average_score = if game_data.save_data.total_games > 0 {
+        game_data.save_data.total_score as f32 / game_data.save_data.total_games as f32
+    } else {
+        0.0
+    };
     commands.spawn((
-        Text2d::new(format!(
-            "📊 游戏统计\n\n总游戏次数: {}\n总得分: {}\n平均分数: {:.1}\n最高分: {}",
-            game_data.save_data.total_games,
-            game_data.save_data.total_score,
-            if game_data.save_data.total_games > 0 {
-                game_data.save_data.total_score as f32 / game_data.save_data.total_games as f32
-            } else {
-                0.0
-            },
-            game_data.save_data.high_score
+        Text2d::new(locale.format(
+            "leaderboard.stats",
+            &[
+                ("games", &game_data.save_data.total_games),
+                ("total", &game_data.save_data.total_score),
+                ("average", &format!("{:.1}", average_score)),
+                ("best", &game_data.save_data.high_score),
+            ],
         )),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 24.0,
             ..default()
         },
@@ -272,9 +362,9 @@ pub fn setup_leaderboard(
     
     // 返回提示
     commands.spawn((
-        Text2d::new("按 ESC 键返回主菜单"),
+        Text2d::new(locale.get("leaderboard.back")),
         TextFont {
-            font: assets.font.clone(),
+            font: assets.font(locale.language()),
             font_size: 24.0,
             ..default()
         },
@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// 天空主题：随对局推进循环昼夜、跟随本地时间，或固定在某个时段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SkyTheme {
+    #[default]
+    Cycle,     // 每局从清晨开始，随对局时间依次经过白天、黄昏和夜晚
+    LocalTime, // 跟随电脑的本地时间
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+impl SkyTheme {
+    // 界面文字的键，见 assets/locales
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            SkyTheme::Cycle => "sky.cycle",
+            SkyTheme::LocalTime => "sky.local_time",
+            SkyTheme::Dawn => "sky.dawn",
+            SkyTheme::Day => "sky.day",
+            SkyTheme::Dusk => "sky.dusk",
+            SkyTheme::Night => "sky.night",
+        }
+    }
+
+    pub fn next(&self) -> SkyTheme {
+        match self {
+            SkyTheme::Cycle => SkyTheme::LocalTime,
+            SkyTheme::LocalTime => SkyTheme::Dawn,
+            SkyTheme::Dawn => SkyTheme::Day,
+            SkyTheme::Day => SkyTheme::Dusk,
+            SkyTheme::Dusk => SkyTheme::Night,
+            SkyTheme::Night => SkyTheme::Cycle,
+        }
+    }
+
+    // 固定时段对应的关键帧名称
+    fn keyframe_name(&self) -> Option<&'static str> {
+        match self {
+            SkyTheme::Dawn => Some("dawn"),
+            SkyTheme::Day => Some("day"),
+            SkyTheme::Dusk => Some("dusk"),
+            SkyTheme::Night => Some("night"),
+            SkyTheme::Cycle | SkyTheme::LocalTime => None,
+        }
+    }
+}
+
+// 天气：在场景前方飘落的粒子，纯装饰
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+}
+
+// 天气粒子的参数
+pub struct WeatherParticles {
+    pub rate: f32,         // 每秒生成的粒子数
+    pub size: [f32; 2],    // 粒子尺寸（像素）
+    pub velocity: [f32; 2], // 基础速度（像素/秒）
+    pub jitter: f32,        // 横向速度的随机范围（±）
+    pub color: [f32; 4],
+}
+
+impl Weather {
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            Weather::Clear => "weather.clear",
+            Weather::Rain => "weather.rain",
+            Weather::Snow => "weather.snow",
+        }
+    }
+
+    pub fn next(&self) -> Weather {
+        match self {
+            Weather::Clear => Weather::Rain,
+            Weather::Rain => Weather::Snow,
+            Weather::Snow => Weather::Clear,
+        }
+    }
+
+    pub fn particles(&self) -> Option<WeatherParticles> {
+        match self {
+            Weather::Clear => None,
+            Weather::Rain => Some(WeatherParticles {
+                rate: 120.0,
+                size: [2.0, 14.0],
+                velocity: [-80.0, -700.0],
+                jitter: 20.0,
+                color: [0.8, 0.85, 1.0, 0.5],
+            }),
+            Weather::Snow => Some(WeatherParticles {
+                rate: 30.0,
+                size: [4.0, 4.0],
+                velocity: [-30.0, -80.0],
+                jitter: 30.0,
+                color: [1.0, 1.0, 1.0, 0.9],
+            }),
+        }
+    }
+}
+
+// 一天中某个时刻的外观
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkyKeyframe {
+    pub name: String,
+    pub at: f32,        // 在一天中的位置，0 为午夜，0.5 为正午
+    pub sky: [f32; 3],  // 清屏颜色
+    pub tint: [f32; 3], // 背景层、地面和障碍物的颜色
+    #[serde(default)]
+    pub layers: HashMap<String, f32>, // 视差背景层名称 -> 不透明度，未列出的层为 1
+}
+
+// 混合后的外观
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkyLook {
+    pub sky: [f32; 3],
+    pub tint: [f32; 3],
+    pub layers: HashMap<String, f32>,
+}
+
+impl SkyLook {
+    pub fn layer_alpha(&self, layer: &str) -> f32 {
+        self.layers.get(layer).copied().unwrap_or(1.0)
+    }
+}
+
+// 本地时间在一天中的位置，0 为午夜
+pub fn local_day_fraction() -> f32 {
+    chrono::Local::now().num_seconds_from_midnight() as f32 / 86_400.0
+}
+
+// 昼夜变化，由 assets/backgrounds/sky.json 描述
+#[derive(Resource, Debug, Clone, Deserialize)]
+pub struct SkyCycle {
+    pub cycle_secs: f32,  // 昼夜循环模式下一整天对应的对局秒数
+    pub cycle_start: f32, // 昼夜循环模式下每局开始时在一天中的位置
+    pub keyframes: Vec<SkyKeyframe>, // 按 at 从小到大排列
+}
+
+impl SkyCycle {
+    pub fn builtin() -> Self {
+        serde_json::from_str(include_str!("../assets/backgrounds/sky.json")).expect("内置天空描述格式错误")
+    }
+
+    // 主题在对局第 elapsed 秒、本地时间为一天中 local_time 处的外观
+    pub fn look(&self, theme: SkyTheme, elapsed: f32, local_time: f32) -> SkyLook {
+        let at = match theme {
+            SkyTheme::Cycle => self.cycle_start + elapsed / self.cycle_secs.max(1.0),
+            SkyTheme::LocalTime => local_time,
+            fixed => fixed
+                .keyframe_name()
+                .and_then(|name| self.keyframes.iter().find(|keyframe| keyframe.name == name))
+                .map_or(0.5, |keyframe| keyframe.at),
+        };
+        self.sample(at)
+    }
+
+    // 在相邻两个关键帧之间线性混合，一天的首尾相接
+    pub fn sample(&self, at: f32) -> SkyLook {
+        let at = at.rem_euclid(1.0);
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return SkyLook { sky: [0.34, 0.75, 0.79], tint: [1.0; 3], layers: HashMap::new() };
+        };
+        let (from, to, t) = match self.keyframes.windows(2).find(|pair| at < pair[1].at) {
+            Some(pair) if at >= pair[0].at => (&pair[0], &pair[1], (at - pair[0].at) / (pair[1].at - pair[0].at)),
+            // 最后一个关键帧之后、第一个关键帧之前，跨过午夜
+            _ => {
+                let span = first.at + 1.0 - last.at;
+                let offset = (at - last.at).rem_euclid(1.0);
+                (last, first, if span > 0.0 { offset / span } else { 0.0 })
+            }
+        };
+        let mix = |a: [f32; 3], b: [f32; 3]| std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t);
+        let layers = from
+            .layers
+            .keys()
+            .chain(to.layers.keys())
+            .map(|name| {
+                let (a, b) = (from.layers.get(name).unwrap_or(&1.0), to.layers.get(name).unwrap_or(&1.0));
+                (name.clone(), a + (b - a) * t)
+            })
+            .collect();
+        SkyLook { sky: mix(from.sky, to.sky), tint: mix(from.tint, to.tint), layers }
+    }
+}
@@ -0,0 +1,64 @@
+// 集成测试共用的无头游戏：MinimalPlugins + SimulationPlugin，每次更新推进一个固定步
+// 各测试文件只用到其中一部分函数
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use flappy_bird::character::CharacterRegistry;
+use flappy_bird::collision::CollisionMasks;
+use flappy_bird::components::BirdCharacter;
+use flappy_bird::difficulty::{DifficultyCurve, DifficultyCurves, DifficultyPreset};
+use flappy_bird::resources::*;
+use flappy_bird::states::GameState;
+use flappy_bird::SimulationPlugin;
+
+pub fn load_characters() -> CharacterRegistry {
+    CharacterRegistry::load_from_dir(Path::new("assets")).expect("读取角色描述失败")
+}
+
+// 读取难度预设的描述文件
+pub fn curve(preset: DifficultyPreset) -> DifficultyCurve {
+    DifficultyCurves::load_from_dir(Path::new("assets")).expect("读取难度描述失败").get(preset)
+}
+
+// 创建无头游戏并进入准备阶段；每次 update 推进 1/tick_rate 秒
+pub fn new_app(characters: &CharacterRegistry, tick_rate: f64, config: GameConfig, character: &BirdCharacter) -> App {
+    let mut app = build_app(characters, tick_rate, config, character);
+    start(&mut app);
+    app
+}
+
+// 创建还停留在菜单的无头游戏，测试可以在开局前添加自己的系统和资源
+pub fn build_app(characters: &CharacterRegistry, tick_rate: f64, config: GameConfig, character: &BirdCharacter) -> App {
+    let masks = CollisionMasks::load_from_dir(Path::new("assets"), characters).expect("读取碰撞遮罩失败");
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimulationPlugin { tick_rate }))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / tick_rate)))
+        .insert_resource(characters.clone())
+        .insert_resource(masks)
+        .insert_resource(config);
+    app.world_mut().resource_mut::<GameData>().selected_character = character.clone();
+    app.update();
+    app
+}
+
+// 从菜单进入一局，停在准备阶段
+pub fn start(app: &mut App) {
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Playing);
+    app.update();
+}
+
+pub fn state(app: &App) -> GameState {
+    app.world().resource::<State<GameState>>().get().clone()
+}
+
+pub fn tick(app: &App) -> u64 {
+    app.world().resource::<SimulationClock>().tick
+}
+
+pub fn score(app: &App) -> u32 {
+    app.world().resource::<GameData>().score
+}
@@ -0,0 +1,247 @@
+// 可达性约束生成的关卡一定可以通过：
+// 先让不会撞击的小鸟跑一遍记录整条关卡，再搜索一条能通过所有管道的拍打序列，
+// 最后在新的一局中按这个序列拍打，确认小鸟真的能通过
+
+mod common;
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use common::{curve, load_characters, score, state};
+use flappy_bird::character::{CharacterManifest, CharacterRegistry};
+use flappy_bird::collision::world_rect;
+use flappy_bird::components::*;
+use flappy_bird::course::BIRD_X;
+use flappy_bird::difficulty::{Curve, DifficultyCurve, DifficultyPreset};
+use flappy_bird::events::FlapEvent;
+use flappy_bird::resources::*;
+use flappy_bird::states::GameState;
+
+const TICK_RATE: f64 = 60.0;
+
+// 每局需要通过的管道数
+const TARGET_SCORE: u32 = 20;
+
+// 每条难度曲线测试的种子数
+const SEEDS: u64 = 8;
+
+// 与小鸟横向距离超过该值的障碍物不做碰撞检测
+const NEAR_DISTANCE: f32 = 200.0;
+
+// 搜索时把小鸟的碰撞框放大一点，搜索结果在真实游戏中留有余量
+const SEARCH_MARGIN: f32 = 1.0;
+
+// 记录下来的整条关卡
+struct Course {
+    stats: CharacterStats,
+    dt: f32,
+    bounds: PlayfieldBounds,
+    bird_scale: Vec3,
+    bird_shape: ColliderShape,
+    obstacles: Vec<Vec<Rect>>, // 每个固定步结束时小鸟附近障碍物的碰撞区域
+    aims: Vec<f32>,            // 每个固定步时小鸟前方最近的通道中心，用于搜索时优先尝试的方向
+}
+
+impl Course {
+    fn step(&self, y: f32, vy: f32, flap: bool) -> (f32, f32) {
+        let vy = (if flap { self.stats.flap_impulse } else { vy } - self.stats.gravity * self.dt)
+            .max(-self.stats.terminal_velocity);
+        (y + vy * self.dt, vy)
+    }
+
+    // 第 tick 个固定步结束时小鸟是否撞击
+    fn collides(&self, tick: usize, y: f32, vy: f32) -> bool {
+        let angle = (vy / self.stats.tilt_velocity).clamp(-1.0, 1.0) * BIRD_MAX_TILT;
+        let bird = Transform::from_xyz(BIRD_X, y, 1.0)
+            .with_rotation(Quat::from_rotation_z(angle))
+            .with_scale(self.bird_scale);
+        self.bird_shape.segments.iter().any(|segment| {
+            let bird = world_rect(&bird, *segment).inflate(SEARCH_MARGIN);
+            bird.min.y < self.bounds.floor
+                || bird.max.y > self.bounds.ceiling
+                || self.obstacles[tick].iter().any(|obstacle| !bird.intersect(*obstacle).is_empty())
+        })
+    }
+
+    // 深度优先搜索通过整条关卡的拍打序列，优先朝前方的通道中心飞；
+    // 拍打后的速度序列与历史无关，速度相同、高度相差不到半个像素的失败状态视为同一个状态
+    fn search(&self, tick: usize, y: f32, vy: f32, dead: &mut HashSet<(usize, i32, u32)>, flaps: &mut Vec<bool>) -> bool {
+        if tick == self.obstacles.len() {
+            return true;
+        }
+        let key = (tick, (y * 2.0).round() as i32, vy.to_bits());
+        if dead.contains(&key) {
+            return false;
+        }
+        let preferred = y < self.aims[tick] && vy < 0.0;
+        for flap in [preferred, !preferred] {
+            let (next_y, next_vy) = self.step(y, vy, flap);
+            flaps.push(flap);
+            if !self.collides(tick, next_y, next_vy) && self.search(tick + 1, next_y, next_vy, dead, flaps) {
+                return true;
+            }
+            flaps.pop();
+        }
+        dead.insert(key);
+        false
+    }
+
+    // 第 0 个固定步是开局的那次拍打
+    fn solve(&self) -> Option<Vec<bool>> {
+        let mut flaps = vec![true];
+        let (y, vy) = self.step(0.0, 0.0, true);
+        let solved = !self.collides(0, y, vy) && self.search(1, y, vy, &mut HashSet::new(), &mut flaps);
+        solved.then_some(flaps)
+    }
+}
+
+fn new_app(
+    characters: &CharacterRegistry,
+    curve: &DifficultyCurve,
+    theme: PipeTheme,
+    character: &BirdCharacter,
+    seed: u64,
+) -> App {
+    let config = GameConfig {
+        seed: Some(seed),
+        pipe_theme: theme,
+        ..GameConfig::with_difficulty(curve.clone())
+    };
+    common::new_app(characters, TICK_RATE, config, character)
+}
+
+fn tick(app: &App) -> usize {
+    common::tick(app) as usize
+}
+
+// 移除小鸟的碰撞体后跑一遍，记录到通过目标分数为止的整条关卡
+// 关卡只由种子、分数和时间决定，与小鸟的飞行路线无关
+fn record(mut app: App) -> Course {
+    let mut birds = app
+        .world_mut()
+        .query_filtered::<(Entity, &Transform, &ColliderShape, &CharacterStats), With<Bird>>();
+    let (bird, transform, shape, stats) = birds.single(app.world()).expect("小鸟没有碰撞形状");
+    let (bird_scale, bird_shape, stats) = (transform.scale, shape.clone(), *stats);
+    app.world_mut().entity_mut(bird).remove::<Collider>();
+
+    let mut course = Course {
+        stats,
+        dt: app.world().resource::<Time<Fixed>>().timestep().as_secs_f32(),
+        bounds: *app.world().resource::<PlayfieldBounds>(),
+        bird_scale,
+        bird_shape,
+        obstacles: Vec::new(),
+        aims: Vec::new(),
+    };
+    app.world_mut().send_event(FlapEvent);
+    while score(&app) < TARGET_SCORE {
+        app.update();
+        if tick(&app) == course.obstacles.len() {
+            continue;
+        }
+        assert_eq!(tick(&app), course.obstacles.len() + 1, "每次更新应当只推进一个固定步");
+
+        let mut pairs = app.world_mut().query_filtered::<&Transform, With<PipePair>>();
+        let aim = pairs
+            .iter(app.world())
+            .map(|pair| pair.translation)
+            .filter(|pair| pair.x > BIRD_X - NEAR_DISTANCE / 2.0)
+            .min_by(|a, b| a.x.total_cmp(&b.x))
+            .map_or(0.0, |pair| pair.y);
+        let mut pipes = app.world_mut().query::<(&Transform, &ColliderShape, &ChildOf)>();
+        let obstacles = pipes
+            .iter(app.world())
+            .filter_map(|(local, shape, child_of)| {
+                let pair = app.world().get::<Transform>(child_of.parent())?;
+                ((pair.translation.x - BIRD_X).abs() <= NEAR_DISTANCE).then(|| {
+                    let pipe = pair.mul_transform(*local);
+                    shape.segments.iter().map(move |segment| world_rect(&pipe, *segment))
+                })
+            })
+            .flatten()
+            .collect();
+        course.obstacles.push(obstacles);
+        course.aims.push(aim);
+    }
+    course
+}
+
+// 按拍打序列玩一局，返回撞击前通过的管道数
+fn replay(mut app: App, flaps: &[bool]) -> u32 {
+    while score(&app) < TARGET_SCORE && tick(&app) < flaps.len() {
+        if flaps[tick(&app)] {
+            app.world_mut().send_event(FlapEvent);
+        }
+        app.update();
+        if state(&app) != GameState::Playing {
+            break;
+        }
+    }
+    score(&app)
+}
+
+// 内置难度之外再加一条极限曲线：通道最窄、前后管道在小鸟身边重叠，
+// 不做可达性约束时这条曲线的大部分种子都无法通过
+fn curves() -> Vec<DifficultyCurve> {
+    let mut curves: Vec<_> = [DifficultyPreset::Normal, DifficultyPreset::Hard, DifficultyPreset::Insane]
+        .into_iter()
+        .map(curve)
+        .collect();
+    curves.push(DifficultyCurve {
+        name: "极限".to_string(),
+        gap: Curve(vec![[0.0, 80.0]]),
+        pipe_speed: Curve(vec![[0.0, 200.0]]),
+        spawn_interval: Curve(vec![[0.0, 0.6]]),
+        gap_center_range: Curve(vec![[0.0, 100.0]]),
+        motion_difficulty: Curve(vec![[0.0, 3.0]]),
+        ..curve(DifficultyPreset::Insane)
+    });
+    curves
+}
+
+#[test]
+fn generated_courses_are_survivable() {
+    let characters = load_characters();
+    let ids = characters.ids();
+    for curve in curves() {
+        for seed in 0..SEEDS {
+            let theme = if seed % 2 == 0 { PipeTheme::Mixed } else { PipeTheme::Festival };
+            let character = &ids[seed as usize % ids.len()];
+            let context = format!("{} 种子 {seed}（{theme:?}，{}）", curve.name, character.0);
+
+            let course = record(new_app(&characters, &curve, theme, character, seed));
+            let flaps = course.solve().unwrap_or_else(|| panic!("{context} 找不到能通过的拍打序列"));
+            let score = replay(new_app(&characters, &curve, theme, character, seed), &flaps);
+            assert!(score >= TARGET_SCORE, "{context} 按搜索结果拍打却在第 {score} 对管道撞击");
+        }
+    }
+}
+
+// 重力大、拍打弱的角色在两对管道之间升降的距离很小，通道中心的活动范围最大时，
+// 可达范围经常落在活动范围之外，生成器只能加宽通道
+fn heavy_character(characters: &CharacterRegistry) -> CharacterManifest {
+    let mut heavy = characters.get(&BirdCharacter::default()).expect("缺少经典小鸟").clone();
+    heavy.id = BirdCharacter::new("Heavy");
+    heavy.stats.gravity = 1800.0;
+    heavy.stats.flap_impulse = 330.0;
+    heavy
+}
+
+#[test]
+fn heavy_characters_survive_the_widest_range() {
+    let builtin = load_characters();
+    let heavy = heavy_character(&builtin);
+    let character = heavy.id.clone();
+    let characters = CharacterRegistry::new(builtin.iter().cloned().chain([heavy]).collect());
+
+    let curve = curves().pop().expect("缺少极限曲线");
+    for seed in 0..SEEDS {
+        let theme = if seed % 2 == 0 { PipeTheme::Mixed } else { PipeTheme::Festival };
+        let context = format!("{} 种子 {seed}（{theme:?}，{}）", curve.name, character.0);
+
+        let course = record(new_app(&characters, &curve, theme, &character, seed));
+        let flaps = course.solve().unwrap_or_else(|| panic!("{context} 找不到能通过的拍打序列"));
+        let score = replay(new_app(&characters, &curve, theme, &character, seed), &flaps);
+        assert!(score >= TARGET_SCORE, "{context} 按搜索结果拍打却在第 {score} 对管道撞击");
+    }
+}
@@ -0,0 +1,22 @@
+// 附带的字体能显示完整的英文界面：
+// 没有中文字体的系统上界面退回英文，英文的字符串表和角色名称必须全部能用附带的字体显示
+
+use std::path::Path;
+
+use flappy_bird::asset_check::{language_texts, FontChain};
+use flappy_bird::locale::Language;
+
+#[test]
+fn bundled_font_covers_the_english_interface() {
+    let chain = FontChain::load(Path::new("assets"));
+    assert!(chain.problems.is_empty(), "字体链有问题: {:?}", chain.problems);
+    let index = chain.font_for(Language::En).expect("没有可以显示英文界面的字体");
+    assert_eq!(index, 0, "英文界面应当使用附带的字体");
+}
+
+#[test]
+fn language_texts_include_character_names() {
+    let texts = language_texts(Path::new("assets"));
+    assert!(texts[&Language::En].contains("Blue Bird"), "英文文字应当包含角色名称");
+    assert!(texts[&Language::ZhCn].contains("蓝色小鸟"), "中文文字应当包含角色名称");
+}
@@ -0,0 +1,132 @@
+// 幽灵小鸟与本局飞过同一条关卡：
+// 开启幽灵时本局使用最佳回放的种子和角色，生成的管道布局与按该种子开局完全一致
+
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use common::{build_app, curve, load_characters, start};
+use flappy_bird::character::CharacterRegistry;
+use flappy_bird::components::*;
+use flappy_bird::difficulty::DifficultyPreset;
+use flappy_bird::events::FlapEvent;
+use flappy_bird::replay::*;
+use flappy_bird::resources::*;
+use flappy_bird::settings::Settings;
+use flappy_bird::states::GameState;
+use flappy_bird::systems::{select_ghost_run, setup_game};
+
+const TICK_RATE: f64 = 60.0;
+
+// 最佳回放的种子
+const BEST_SEED: u64 = 42;
+
+// 比较管道布局时运行的固定步数
+const TICKS: usize = 60 * 10;
+
+// 每个固定步生成的管道对：生成时的固定步和位置
+#[derive(Resource, Default)]
+struct SpawnedPairs(Vec<(u64, Vec3)>);
+
+fn collect_spawned(
+    clock: Res<SimulationClock>,
+    mut spawned: ResMut<SpawnedPairs>,
+    pairs: Query<&Transform, Added<PipePair>>,
+) {
+    for pair in pairs.iter() {
+        spawned.0.push((clock.tick, pair.translation));
+    }
+}
+
+fn config(seed: Option<u64>) -> GameConfig {
+    GameConfig {
+        seed,
+        pipe_theme: PipeTheme::Mixed,
+        ..GameConfig::with_difficulty(curve(DifficultyPreset::Normal))
+    }
+}
+
+// 在存档目录中保存一份最佳回放，角色与本局选择的角色不同
+fn save_best(dir: &Path, characters: &CharacterRegistry) -> Replay {
+    let character = characters.ids().into_iter().find(|id| *id != BirdCharacter::default()).expect("至少需要两个角色");
+    let mut best = Replay::new(
+        BEST_SEED,
+        Duration::from_secs_f64(1.0 / TICK_RATE),
+        ReplayConfig::from_config(&config(None)),
+        characters.get(&character).expect("角色不存在"),
+    );
+    best.ticks = TICKS as u64;
+    let save_manager = SaveManager { save_path: dir.join("save_data.json") };
+    best.save(&save_manager.replay_path(BEST_REPLAY)).expect("保存最佳回放失败");
+    best
+}
+
+// 开启幽灵的无头游戏：与客户端一样在 setup_game 之前选出最佳回放
+fn ghost_app(dir: &Path, characters: &CharacterRegistry, config: GameConfig, run_mode: RunMode) -> App {
+    let mut app = build_app(characters, TICK_RATE, config, &BirdCharacter::default());
+    app.insert_resource(Settings::default())
+        .insert_resource(run_mode)
+        .insert_resource(SaveManager { save_path: dir.join("save_data.json") })
+        .add_systems(OnEnter(GameState::Playing), select_ghost_run.before(setup_game));
+    start(&mut app);
+    app
+}
+
+// 移除小鸟的碰撞体后运行，记录生成的管道布局和本局的角色
+fn course(mut app: App) -> (Vec<(u64, Vec3)>, BirdCharacter) {
+    app.init_resource::<SpawnedPairs>().add_systems(FixedPostUpdate, collect_spawned);
+    let mut birds = app.world_mut().query::<(Entity, &Bird)>();
+    let (bird, character) = birds
+        .single(app.world())
+        .map(|(entity, bird)| (entity, bird.character.clone()))
+        .expect("没有小鸟");
+    app.world_mut().entity_mut(bird).remove::<Collider>();
+
+    app.world_mut().send_event(FlapEvent);
+    for _ in 0..TICKS {
+        app.update();
+    }
+    (app.world_mut().remove_resource::<SpawnedPairs>().expect("没有管道记录").0, character)
+}
+
+#[test]
+fn ghost_and_live_run_share_the_course() {
+    let dir = std::env::temp_dir().join(format!("flappy_bird_ghost_test_{}", std::process::id()));
+    let characters = load_characters();
+    let best = save_best(&dir, &characters);
+
+    let app = ghost_app(&dir, &characters, config(None), RunMode::Normal);
+    assert!(app.world().contains_resource::<GhostRun>(), "最佳回放应当被选为幽灵");
+    let (live, live_character) = course(app);
+    let (recorded, _) = course(common::new_app(&characters, TICK_RATE, config(Some(BEST_SEED)), &best.character));
+
+    assert_eq!(live_character, best.character, "本局应当使用最佳回放的角色");
+    assert!(!live.is_empty(), "{TICKS} 个固定步内没有生成管道");
+    assert_eq!(live, recorded, "本局与最佳回放的管道布局不一致");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn ghost_is_skipped_when_the_seed_is_fixed_to_another_course() {
+    let dir = std::env::temp_dir().join(format!("flappy_bird_ghost_seed_test_{}", std::process::id()));
+    let characters = load_characters();
+    save_best(&dir, &characters);
+
+    let app = ghost_app(&dir, &characters, config(Some(BEST_SEED + 1)), RunMode::Normal);
+    assert!(!app.world().contains_resource::<GhostRun>(), "种子不同的最佳回放不应当显示为幽灵");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn ghost_is_skipped_in_daily_runs() {
+    let dir = std::env::temp_dir().join(format!("flappy_bird_ghost_daily_test_{}", std::process::id()));
+    let characters = load_characters();
+    save_best(&dir, &characters);
+
+    let daily = RunMode::Daily { date: "2026-01-01".to_string() };
+    let app = ghost_app(&dir, &characters, config(None), daily);
+    assert!(!app.world().contains_resource::<GhostRun>(), "每日挑战不应当显示幽灵");
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,101 @@
+// 每对管道无论帧率和模拟频率如何都只计一分：
+// 移除小鸟的碰撞体后按不同的模拟频率和更新间隔各跑一遍，统计每对管道发出的 PipePassed 事件
+
+mod common;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use common::{curve, load_characters, score};
+use flappy_bird::components::*;
+use flappy_bird::difficulty::{Curve, DifficultyCurve, DifficultyPreset};
+use flappy_bird::events::{FlapEvent, PipePassed};
+use flappy_bird::resources::*;
+
+// 每种频率需要通过的管道数
+const TARGET_PAIRS: u32 = 24;
+
+// 单次运行最多模拟的秒数
+const MAX_SECS: f64 = 120.0;
+
+// 每对管道收到的计分事件，以及这对管道的运动模式
+#[derive(Resource, Default)]
+struct PassedPairs(HashMap<Entity, (u32, PipeMotionPattern)>);
+
+fn collect_passed(
+    mut passed_events: EventReader<PipePassed>,
+    pair_query: Query<&PipeMotion, With<PipePair>>,
+    mut passed: ResMut<PassedPairs>,
+) {
+    for event in passed_events.read() {
+        let pattern = pair_query.get(event.pair).expect("计分的管道对不存在").pattern;
+        passed.0.entry(event.pair).or_insert((0, pattern)).0 += 1;
+    }
+}
+
+// 运动模式出现得最多的曲线：运动中的通道会在计分线附近上下移动
+// 通道要足够高，运动后仍能穿过，否则生成器会改为静止通道
+fn moving_curve() -> DifficultyCurve {
+    DifficultyCurve {
+        name: "运动".to_string(),
+        gap: Curve(vec![[0.0, 240.0]]),
+        motion_difficulty: Curve(vec![[0.0, 3.0]]),
+        gap_center_range: Curve(vec![[0.0, 100.0]]),
+        ..curve(DifficultyPreset::Normal)
+    }
+}
+
+// 按 tick_rate 模拟，每次更新经过 frame_secs 秒；返回每对管道的计分次数和运动模式
+fn run(tick_rate: f64, frame_secs: f64) -> (u32, PassedPairs) {
+    let config = GameConfig {
+        seed: Some(7),
+        ..GameConfig::with_difficulty(moving_curve())
+    };
+    let mut app = common::new_app(&load_characters(), tick_rate, config, &BirdCharacter::default());
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(frame_secs)))
+        .init_resource::<PassedPairs>()
+        .add_systems(FixedPostUpdate, collect_passed);
+
+    // 小鸟不会撞击，计分只取决于管道的位置
+    let mut birds = app.world_mut().query_filtered::<Entity, With<Bird>>();
+    let bird = birds.single(app.world()).expect("没有小鸟");
+    app.world_mut().entity_mut(bird).remove::<Collider>();
+
+    app.world_mut().send_event(FlapEvent);
+    let mut elapsed = 0.0;
+    while score(&app) < TARGET_PAIRS && elapsed < MAX_SECS {
+        app.update();
+        elapsed += frame_secs;
+    }
+    let passed = app.world_mut().remove_resource::<PassedPairs>().expect("缺少计分记录");
+    (score(&app), passed)
+}
+
+#[test]
+fn each_pair_scores_once_at_any_rate() {
+    // 模拟频率和更新间隔：一帧一步、一帧多步、多帧一步
+    let rates = [(60.0, 1.0 / 60.0), (144.0, 1.0 / 144.0), (30.0, 1.0 / 30.0), (60.0, 1.0 / 144.0), (60.0, 1.0 / 24.0)];
+    for (tick_rate, frame_secs) in rates {
+        let context = format!("模拟频率 {tick_rate}，每帧 {:.1} 毫秒", frame_secs * 1000.0);
+        let (score, passed) = run(tick_rate, frame_secs);
+        assert!(score >= TARGET_PAIRS, "{context} 只通过了 {score} 对管道");
+
+        let events: u32 = passed.0.values().map(|(count, _)| count).sum();
+        assert_eq!(events, score, "{context} 的计分事件数与分数不一致");
+        for (pair, (count, pattern)) in &passed.0 {
+            assert_eq!(*count, 1, "{context} 管道对 {pair:?}（{pattern:?}）计分 {count} 次");
+        }
+
+        let moving = |matches: fn(&PipeMotionPattern) -> bool| passed.0.values().filter(|(_, pattern)| matches(pattern)).count();
+        assert!(
+            moving(|pattern| matches!(pattern, PipeMotionPattern::Oscillate { .. })) > 0,
+            "{context} 没有通过上下摆动的管道对"
+        );
+        assert!(
+            moving(|pattern| matches!(pattern, PipeMotionPattern::Drift { .. })) > 0,
+            "{context} 没有通过漂移的管道对"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// 回放能够复现录制的对局：
+// 自动驾驶的小鸟通过几对管道后停止拍打并坠地，录制的回放保存到文件后在新的游戏中播放，
+// 分数和撞击时的固定步必须完全一致
+
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use common::{curve, load_characters, score, state, tick};
+use flappy_bird::character::CharacterRegistry;
+use flappy_bird::components::*;
+use flappy_bird::course::BIRD_X;
+use flappy_bird::difficulty::DifficultyPreset;
+use flappy_bird::events::FlapEvent;
+use flappy_bird::replay::*;
+use flappy_bird::resources::*;
+use flappy_bird::states::GameState;
+
+const TICK_RATE: f64 = 60.0;
+
+// 自动驾驶通过这么多对管道后停止拍打
+const AUTOPILOT_SCORE: u32 = 5;
+
+// 单局最多运行的固定步数
+const MAX_TICKS: u64 = 60 * 120;
+
+fn crashed(app: &App) -> bool {
+    app.world().resource::<SimulationClock>().crashed
+}
+
+// 朝前方最近的通道中心飞：低于通道中心并且正在下落时拍打
+fn autopilot_flap(app: &mut App) -> bool {
+    let mut birds = app.world_mut().query_filtered::<(&Transform, &Velocity), With<Bird>>();
+    let Ok((bird, velocity)) = birds.single(app.world()) else {
+        return false;
+    };
+    let (y, vy) = (bird.translation.y, velocity.y);
+    let mut pairs = app.world_mut().query_filtered::<&Transform, With<PipePair>>();
+    let aim = pairs
+        .iter(app.world())
+        .map(|pair| pair.translation)
+        .filter(|pair| pair.x > BIRD_X - 40.0)
+        .min_by(|a, b| a.x.total_cmp(&b.x))
+        .map_or(0.0, |pair| pair.y);
+    y < aim - 10.0 && vy <= 0.0
+}
+
+// 玩一局直到撞击，返回录制的回放
+fn record(seed: u64, character: &BirdCharacter) -> Replay {
+    let characters = load_characters();
+    let config = GameConfig {
+        seed: Some(seed),
+        pipe_theme: PipeTheme::Mixed,
+        ..GameConfig::with_difficulty(curve(DifficultyPreset::Normal))
+    };
+    let mut app = common::new_app(&characters, TICK_RATE, config, character);
+
+    app.world_mut().send_event(FlapEvent);
+    app.update();
+    while !crashed(&app) && tick(&app) < MAX_TICKS {
+        if score(&app) < AUTOPILOT_SCORE && autopilot_flap(&mut app) {
+            app.world_mut().send_event(FlapEvent);
+        }
+        app.update();
+    }
+    assert!(crashed(&app), "种子 {seed} 的对局在 {MAX_TICKS} 个固定步内没有结束");
+
+    let mut replay = app.world().resource::<ReplayRecorder>().0.clone();
+    replay.score = score(&app);
+    replay.ticks = tick(&app);
+    replay
+}
+
+// 在另一套配置的新游戏中播放回放，返回最终的分数和固定步
+fn play_back(replay: Replay) -> (u32, u64) {
+    let characters = load_characters();
+    let config = GameConfig {
+        seed: Some(replay.seed.wrapping_add(1)),
+        pipe_theme: PipeTheme::Festival,
+        ..GameConfig::with_difficulty(curve(DifficultyPreset::Insane))
+    };
+    let other = characters.ids().into_iter().find(|id| *id != replay.character).expect("至少需要两个角色");
+    let mut app = common::new_app(&characters, TICK_RATE, config, &other);
+
+    app.insert_resource(ReplayPlayback::new(replay));
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Replay);
+    app.update();
+    assert_eq!(state(&app), GameState::Replay);
+    while !crashed(&app) && tick(&app) < MAX_TICKS {
+        app.update();
+    }
+    (score(&app), tick(&app))
+}
+
+#[test]
+fn replays_reproduce_score_and_tick() {
+    let dir = std::env::temp_dir().join(format!("flappy_bird_replay_test_{}", std::process::id()));
+    let ids = load_characters().ids();
+    for seed in 0..4 {
+        let character = &ids[seed as usize % ids.len()];
+        let recorded = record(seed, character);
+        assert!(recorded.score > 0, "种子 {seed} 的自动驾驶没有通过任何管道，无法验证计分");
+
+        // 经过文件保存和读取，与游戏中的回放流程一致
+        let path = dir.join(format!("{seed}.json"));
+        recorded.save(&path).expect("保存回放失败");
+        let loaded = Replay::load(&path).expect("读取回放失败");
+
+        let (score, tick) = play_back(loaded);
+        assert_eq!(score, recorded.score, "种子 {seed}（{}）回放的分数不一致", character.0);
+        assert_eq!(tick, recorded.ticks, "种子 {seed}（{}）回放的撞击时间不一致", character.0);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn replays_with_other_versions_are_rejected() {
+    let dir = std::env::temp_dir().join(format!("flappy_bird_replay_version_test_{}", std::process::id()));
+    let config = ReplayConfig::from_config(&GameConfig::default());
+    let characters = load_characters();
+    let character = characters.iter().next().expect("没有角色");
+    let timestep = Duration::from_secs_f64(1.0 / TICK_RATE);
+    for version in [REPLAY_VERSION - 1, REPLAY_VERSION + 1] {
+        let mut replay = Replay::new(0, timestep, config.clone(), character);
+        replay.version = version;
+        let path = dir.join(format!("{version}.json"));
+        replay.save(&path).expect("保存回放失败");
+        assert!(Replay::load(&path).is_err(), "版本 {version} 的回放不应当被接受");
+    }
+
+    let current = Replay::new(0, timestep, config, character);
+    let path = dir.join("current.json");
+    current.save(&path).expect("保存回放失败");
+    assert!(Replay::load(&path).is_ok(), "当前版本的回放应当可以读取");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn replays_of_modified_characters_are_rejected() {
+    let characters = load_characters();
+    let recorded_with = characters.iter().next().expect("没有角色").clone();
+    let replay = Replay::new(
+        0,
+        Duration::from_secs_f64(1.0 / TICK_RATE),
+        ReplayConfig::from_config(&GameConfig::default()),
+        &recorded_with,
+    );
+    assert!(replay.check_character(&characters).is_ok(), "未修改的角色应当可以播放");
+
+    // 录制后修改角色的重力，回放不再能够复现
+    let mut modified = recorded_with.clone();
+    modified.stats.gravity += 100.0;
+    let modified = CharacterRegistry::new(vec![modified]);
+    assert!(replay.check_character(&modified).is_err(), "角色描述修改后不应当可以播放");
+
+    let mut app = common::new_app(&modified, TICK_RATE, GameConfig::default(), &recorded_with.id);
+    app.insert_resource(ReplayPlayback::new(replay));
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Replay);
+    app.update();
+    app.update();
+    assert_eq!(state(&app), GameState::Menu, "角色描述修改后的回放应当返回菜单");
+}